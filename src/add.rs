@@ -3,9 +3,16 @@
 use crate::tip::TIP;
 use crate::statics::CONFIG;
 use std::io::Read;
+use std::str::{FromStr};
 use std::{io, process};
 
 
+// Data placeholder in the template when adding interactively
+const INTERACTIVE_DATA: &str = "<replace me>";
+
+// Number of history commands to pick from when not given
+const HISTORY_COMMANDS: usize = 100;
+
 // Enum that describe the source input of the new Tip
 enum Input {
 
@@ -19,6 +26,14 @@ enum Input {
 
     // Means that the Tip contents will need to be manually entered by the user.
     Interactive,
+
+    // Recorded means that the Tip contents is a command and its output, see
+    // record.rs
+    Recorded(String),
+
+    // History means that the Tip contents are commands picked from the shell
+    // history, see history.rs
+    History(String),
 }
 
 // Struct holding input used.
@@ -26,6 +41,29 @@ struct Source {
 
     // This field hold the Input type (see above)
     origin: Input,
+
+    // Subject and data extension to pre-fill in the template. The extension
+    // is detected from the data when not given.
+    subject: Option<String>,
+    extension: Option<String>,
+
+    // If the template is opened in the editor before the Tip is added
+    edit: bool,
+}
+
+impl Source {
+
+    // Associated functions
+
+    // Create a Source for origin, edited and without pre-filled fields
+    fn new(origin: Input) -> Source {
+        Source {
+            origin,
+            subject: None,
+            extension: None,
+            edit: true,
+        }
+    }
 }
 
 
@@ -46,11 +84,32 @@ pub fn add(matches: &clap::ArgMatches) {
 
         // Call function add_tip with input Stdin and String containing
         // the data just read.
-        add_tip(
+        added(add_tip(Source::new(Input::Stdin(lines))));
+
+    } else if matches.is_present("from-history") {
+
+        // Pick commands from the shell history, and add them as data
+        let n = match matches.value_of("from-history") {
+            Some(n) => match usize::from_str(n) {
+                Ok(n) => n,
+                Err(error) => {
+                    panic!("Error converting str {} to usize\n{}", n, error)
+                },
+            },
+            None => HISTORY_COMMANDS,
+        };
+
+        let mut data = crate::history::pick(n).join("\n");
+        data.push('\n');
+
+        added(add_tip(
             Source {
-                origin: Input::Stdin(lines)
+                origin: Input::History(data),
+                subject: None,
+                extension: Some("sh".to_string()),
+                edit: true,
             }
-        );
+        ));
 
     } else {
 
@@ -59,55 +118,140 @@ pub fn add(matches: &clap::ArgMatches) {
         //
         // Otherwise the add must be interactive.
         match matches.value_of("file") {
-            Some(path) => add_tip(
-                Source {
-                    origin: Input::File(path.to_string())
-                }
-            ),
-            None => add_tip(
-                Source {
-                    origin: Input::Interactive
-                }
-            ),
+            Some(path) => added(add_tip(Source::new(Input::File(path.to_string())))),
+            None => added(interactive()),
         };
 
     }
 }
 
-// Help function to add a new Tip
-fn add_tip(source: Source) {
+// Add a new Tip interactively, returns the id of the new Tip or None if
+// the user aborted.
+pub fn interactive() -> Option<usize> {
+    add_tip(Source::new(Input::Interactive))
+}
+
+// Add a new Tip holding a recorded command and its output, with the subject
+// and data extension pre-filled. The template is opened in the editor if
+// edit, otherwise the Tip is added as is. Returns the id of the new Tip or
+// None if the user aborted.
+pub fn recorded(data: String, subject: &str, extension: &str, edit: bool) -> Option<usize> {
+    add_tip(
+        Source {
+            origin: Input::Recorded(data),
+            subject: Some(subject.to_string()),
+            extension: Some(extension.to_string()),
+            edit,
+        }
+    )
+}
 
-    // Write the template to file, and then open it
-    write_template(source);
-    crate::open::editor(&CONFIG.tmp_file);
+// Exit if no Tip was added
+pub fn added(id: Option<usize>) {
+    if id.is_none() {
+        println!("Aborting. Contents no different from template.");
+        process::exit(1)
+    }
+}
+
+// Add a Tip with metadata and data, which may hold parts as written in the
+// template, without editing. Used when importing, so the created date is
+// kept when set. Returns the id of the new Tip.
+pub fn imported(metadata: &crate::metadata::Metadata, data: &str) -> usize {
+    let yaml = match serde_yaml::to_string(metadata) {
+        Ok(yaml) => yaml,
+        Err(error) => {
+            panic!("Error serialize metadata {}\n{}", metadata, error)
+        },
+    };
+
+    let mut tip = create(format!("{}{}\n{}", yaml, TIP.separator, data));
+    tip.metadata.created = metadata.created.or(tip.metadata.created);
+    tip.metadata.id = crate::tips::Tips::next_id();
+    let id = tip.metadata.id.unwrap_or(0);
+    crate::revision::record(&tip, "imported");
+    crate::tips::Tips::add(tip);
+    id
+}
+
+// Help function to add a new Tip. Returns the id of the new Tip or None if
+// the contents did not differ from the template. A source giving the subject,
+// such as a recording, is complete without edits, so it is never aborted.
+fn add_tip(source: Source) -> Option<usize> {
+
+    // Write the template to file, and then open it unless the Tip is added
+    // without editing
+    let edit = source.edit;
+    let complete = source.subject.is_some();
+    let template = write_template(source);
+    if edit {
+        crate::open::editor(&CONFIG.tmp_file);
+    }
 
     // Read the contents of tmp file and remove the tmp file
     let contents = crate::helpers::read_to_string(&CONFIG.tmp_file);
     crate::helpers::remove_file(&CONFIG.tmp_file);
 
     // if contents do not differ from template then abort
-    if &contents[0..TIP.template.len()] == TIP.template {
-        println!("Aborting. Contents no different from template.");
-        process::exit(1)
+    if edit && !complete && contents.starts_with(&template) {
+        return None;
     }
 
     // Create a new Tip from the read data and add it to tips
     let mut tip: crate::tip::Tip = create(contents);
     tip.metadata.id = crate::tips::Tips::next_id();
+    let id = tip.metadata.id;
+    crate::revision::record(&tip, "created");
+    let message = crate::git::message("add", &tip);
     crate::tips::Tips::add(tip);
+    crate::git::commit(&message);
+    id
 }
 
 // Function to write down the template to the temporary file specified
-// in config.
-fn write_template(source: Source) {
+// in config. The subject and data_extension are pre-filled when given by the
+// source, the data_extension otherwise when it can be detected from the data.
+// Returns the template written, excluding the data.
+fn write_template(source: Source) -> String {
+    let subject = source.subject.clone();
+    let extension = source.extension.clone();
 
     // Get the contents to add in data section of template.
     let data = get_tip_data(source);
 
-    // Write the template and the data to temporary file.
+    // Pre-fill the subject and data extension
     let mut template = TIP.template.to_string();
-    template.insert_str(TIP.template.len(), &data);
-    crate::helpers::write_to_file(&CONFIG.tmp_file, &template);
+    if let Some(subject) = subject {
+        template = template.replacen(
+            "subject:\n", &format!("subject: {}\n", quote(&subject)), 1);
+    }
+    if let Some(ext) = extension.or_else(|| detect_extension(&crate::part::split(&data).0)) {
+        template = template.replacen(
+            "data_extension:\n", &format!("data_extension: {}\n", ext), 1);
+    }
+
+    // Write the template and the data to temporary file.
+    let mut contents = template.to_string();
+    contents.push_str(&data);
+    crate::helpers::write_to_file(&CONFIG.tmp_file, &contents);
+
+    template
+}
+
+// Quote text as a single line YAML string
+fn quote(text: &str) -> String {
+    let line = text.replace('\n', " ");
+    format!("'{}'", line.replace('\'', "''"))
+}
+
+// Detect the data extension for the data, None for interactive input since
+// it is only a placeholder.
+fn detect_extension(data: &str) -> Option<String> {
+    if data == INTERACTIVE_DATA {
+        return None;
+    }
+
+    crate::detect::extension(data, &crate::assets::syntax_set())
 }
 
 // This function returns that data for the different sources. That means that
@@ -115,11 +259,15 @@ fn write_template(source: Source) {
 //      file, then return the contents of the file.
 //      stdin, then return the provided data
 //      interactive, then return a "replace me"
+//      recording, then return the recorded command and output
+//      history, then return the picked commands
 fn get_tip_data(s: Source) -> String {
     match s.origin {
-        Input::File(file)   => crate::helpers::read_to_string(&file),
-        Input::Stdin(stdin) => stdin,
-        Input::Interactive  => "<replace me>".to_string(),
+        Input::File(file)     => crate::helpers::read_to_string(&file),
+        Input::Stdin(stdin)   => stdin,
+        Input::Interactive    => INTERACTIVE_DATA.to_string(),
+        Input::Recorded(data) => data,
+        Input::History(data)  => data,
     }
 }
 
@@ -143,7 +291,7 @@ fn create(contents: String) -> crate::tip::Tip {
 
     // First deserialize the Metadata from String, then add the datetime to the
     // created field in Metadata.
-    let mut md: crate::metadata::Metadata = match serde_yaml::from_str(&_metadata) {
+    let mut md: crate::metadata::Metadata = match serde_yaml::from_str(_metadata) {
         Ok(md) => md,
         Err(err) => {
             panic!("Error deserialize metadata {}\n{}",
@@ -153,12 +301,15 @@ fn create(contents: String) -> crate::tip::Tip {
 
     md.created = Some(chrono::offset::Local::now());
 
+    // Split the data from its parts, see part.rs
+    let (data, sections) = crate::part::split(&__data);
+
     // Generate a new uudi for this Tip's data.
     let uuid = uuid::Uuid::new_v4();
 
     crate::helpers::write_to_file(
         &format!("{}/{}", &CONFIG.data, uuid),
-        &__data);
+        &data);
 
-    crate::tip::Tip { metadata: md, data: uuid }
+    crate::tip::Tip { metadata: md, data: uuid, parts: crate::part::create(sections), attachments: Vec::new() }
 }
@@ -0,0 +1,130 @@
+// This file contains functionality to load the syntaxes and themes used to
+// highlight tip data.
+//
+// Besides the syntect defaults, .sublime-syntax files are loaded from the
+// syntaxes directory and .tmTheme files from the themes directory in the
+// tips home directory. Compiling these is slow, so the resulting sets are
+// cached as binary dumps which are rebuilt whenever the directory contents
+// are newer than the dump.
+
+use crate::statics::{CONFIG};
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::parsing::SyntaxSet;
+use std::{fs, path, time};
+
+
+// Theme used when the configured theme does not exist
+const FALLBACK_THEME: &str = "Solarized (dark)";
+
+// Return the directory with user provided syntaxes
+pub fn syntaxes_dir() -> path::PathBuf {
+    CONFIG.home().join("syntaxes")
+}
+
+// Return the directory with user provided themes
+pub fn themes_dir() -> path::PathBuf {
+    CONFIG.home().join("themes")
+}
+
+// Load the default syntaxes and those in the syntaxes directory
+pub fn syntax_set() -> SyntaxSet {
+    let dir = syntaxes_dir();
+    let dump = CONFIG.home().join("syntaxes.packdump");
+
+    if !dir.is_dir() {
+        return SyntaxSet::load_defaults_newlines();
+    }
+
+    if is_fresh(&dump, &dir) {
+        if let Ok(ps) = syntect::dumps::from_dump_file(&dump) {
+            return ps;
+        }
+    }
+
+    let mut builder = SyntaxSet::load_defaults_newlines().into_builder();
+    if let Err(error) = builder.add_from_folder(&dir, true) {
+        panic!("Error loading syntaxes from {:?}\n{}", &dir, error);
+    }
+    let ps = builder.build();
+
+    store_dump(&ps, &dump);
+    ps
+}
+
+// Load the default themes and those in the themes directory
+pub fn theme_set() -> ThemeSet {
+    let dir = themes_dir();
+    let dump = CONFIG.home().join("themes.themedump");
+
+    if !dir.is_dir() {
+        return ThemeSet::load_defaults();
+    }
+
+    if is_fresh(&dump, &dir) {
+        if let Ok(ts) = syntect::dumps::from_dump_file(&dump) {
+            return ts;
+        }
+    }
+
+    let mut ts = ThemeSet::load_defaults();
+    if let Err(error) = ts.add_from_folder(&dir) {
+        panic!("Error loading themes from {:?}\n{}", &dir, error);
+    }
+
+    store_dump(&ts, &dump);
+    ts
+}
+
+// Return the configured theme. If it does not exist warn the user and fall
+// back to the default theme.
+pub fn theme(ts: &ThemeSet) -> &Theme {
+    let name = &CONFIG.style.data.theme;
+    if let Some(theme) = ts.themes.get(name) {
+        return theme;
+    }
+
+    eprintln!("Theme '{}' not found, see 'tips themes'. Using '{}'.",
+              name, FALLBACK_THEME);
+
+    match ts.themes.get(FALLBACK_THEME) {
+        Some(theme) => theme,
+        None => match ts.themes.values().next() {
+            Some(theme) => theme,
+            None        => panic!("Error no themes available"),
+        },
+    }
+}
+
+// Write a binary dump of the set to file
+fn store_dump<T: serde::Serialize>(set: &T, dump: &path::Path) {
+    if let Err(error) = syntect::dumps::dump_to_file(set, dump) {
+        panic!("Error writing dump {:?}\n{}", dump, error);
+    }
+}
+
+// Return true if the dump exists and is newer than the directory and all
+// files within it. Adding or removing files updates the directory itself.
+fn is_fresh(dump: &path::Path, dir: &path::Path) -> bool {
+    let dumped = match modified(dump) {
+        Some(dumped) => dumped,
+        None         => return false,
+    };
+
+    let mut newest = modified(dir);
+    for entry in walkdir::WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+        let entry_modified = modified(entry.path());
+        if entry_modified > newest {
+            newest = entry_modified;
+        }
+    }
+
+    match newest {
+        Some(newest) => newest <= dumped,
+        None         => false,
+    }
+}
+
+// Return the modification time of path, None if not available
+fn modified(path: &path::Path) -> Option<time::SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
@@ -0,0 +1,122 @@
+// This file contains functionality to attach files to a Tip (attach
+// subcommand) and to handle the attachments of a Tip (attachment
+// subcommand). See attachment.rs for how attachments are stored.
+
+use crate::attachment::{Attachment};
+use std::io::{Write};
+use std::{fs, io, path};
+
+
+// Entry point for attach subcommand
+pub fn attach(matches: &clap::ArgMatches) {
+
+    // Since args "id" and "file" are required it is safe to call unwrap here
+    let reference = matches.value_of("id").unwrap();
+    let file = matches.value_of("file").unwrap();
+
+    let contents = match fs::read(file) {
+        Ok(contents) => contents,
+        Err(error) => {
+            panic!("Error reading file {}\n{}", file, error)
+        },
+    };
+
+    // The name defaults to the file name
+    let name = match matches.value_of("name") {
+        Some(name) => name.to_string(),
+        None => match path::Path::new(file).file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None       => panic!("Error unable to get file name of {}", file),
+        },
+    };
+
+    let mut tips = crate::tips::Tips::load();
+    let index = tips.find(reference);
+    let id = tips.tips[index].metadata.id.unwrap_or(0);
+
+    // An attachment with the same name is replaced
+    let attachment = Attachment::store(&name, &contents);
+    let replaced = match tips.tips[index].attachments.iter().position(|a| a.name == name) {
+        Some(position) => Some(std::mem::replace(&mut tips.tips[index].attachments[position], attachment)),
+        None => {
+            tips.tips[index].attachments.push(attachment);
+            None
+        },
+    };
+
+    tips.tips[index].metadata.last_updated = Some(chrono::offset::Local::now());
+    tips.store();
+
+    if let Some(replaced) = replaced {
+        crate::attachment::remove_unused(&replaced, &tips);
+        println!("Replaced attachment {} of tip {}", name, id);
+    }
+    crate::git::commit(&crate::git::message("attach", &tips.tips[index]));
+}
+
+// Entry point for attachment subcommand
+pub fn attachment(matches: &clap::ArgMatches) {
+    match matches.subcommand() {
+        ("get", Some(matches))    => get(matches),
+        ("remove", Some(matches)) => remove(matches),
+        _                         => {
+            println!("{}", matches.usage());
+            std::process::exit(1);
+        },
+    }
+}
+
+// Write the contents of an attachment to the output file, or to stdout
+fn get(matches: &clap::ArgMatches) {
+    let name = matches.value_of("name").unwrap();
+
+    let tips = crate::tips::Tips::load();
+    let tip = &tips.tips[tips.find(matches.value_of("id").unwrap())];
+    let contents = get_attachment(tip, name).get_contents();
+
+    match matches.value_of("output") {
+        Some(output) => {
+            if let Err(error) = fs::write(output, &contents) {
+                panic!("Error writing file {}\n{}", output, error)
+            }
+        },
+        None => {
+            let mut stdout = io::stdout();
+            match stdout.write_all(&contents).and_then(|_| stdout.flush()) {
+                Ok(_) => (),
+                Err(ref error) if error.kind() == io::ErrorKind::BrokenPipe => (),
+                Err(error) => {
+                    panic!("Error when trying to write to stdout\n{}", error)
+                },
+            };
+        },
+    };
+}
+
+// Remove an attachment from a Tip
+fn remove(matches: &clap::ArgMatches) {
+    let name = matches.value_of("name").unwrap();
+
+    let mut tips = crate::tips::Tips::load();
+    let index = tips.find(matches.value_of("id").unwrap());
+    get_attachment(&tips.tips[index], name);
+
+    let position = tips.tips[index].attachments.iter().position(|a| a.name == name).unwrap();
+    let removed = tips.tips[index].attachments.remove(position);
+    tips.tips[index].metadata.last_updated = Some(chrono::offset::Local::now());
+    tips.store();
+
+    crate::attachment::remove_unused(&removed, &tips);
+    crate::git::commit(&crate::git::message("detach", &tips.tips[index]));
+}
+
+// Return the attachment with name of tip, or exit with a message
+fn get_attachment<'a>(tip: &'a crate::tip::Tip, name: &str) -> &'a Attachment {
+    match tip.attachments.iter().find(|a| a.name == name) {
+        Some(attachment) => attachment,
+        None => {
+            eprintln!("No attachment {} in tip with id: {}", name, tip.metadata.id.unwrap_or(0));
+            std::process::exit(1);
+        },
+    }
+}
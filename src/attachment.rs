@@ -0,0 +1,99 @@
+// This file holds definition of a Tip's binary attachments.
+//
+// Attachments are files such as screenshots, PDFs or tarballs attached to a
+// Tip. They are stored content-addressed in the attachments directory of the
+// data directory, named by the SHA-256 of their contents, so the same file
+// attached to several tips is stored once. The contents are handled as bytes
+// and never read as a string.
+
+use crate::statics::{CONFIG};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{fs};
+
+
+// MIME type used when it can not be guessed from the name
+const DEFAULT_MIME: &str = "application/octet-stream";
+
+// Struct describing a file attached to a Tip
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Attachment {
+
+    // The name of the attachment, unique within the Tip. Defaults to the
+    // file name of the attached file.
+    pub name: String,
+
+    // Size in bytes and MIME type of the contents
+    pub size: u64,
+    pub mime: String,
+
+    // SHA-256 of the contents, in hex, which is also the name of the file
+    // holding the contents.
+    pub sha256: String,
+}
+
+impl Attachment {
+
+    // Functions
+
+    // Return the path of the file holding the contents
+    pub fn path(&self) -> String {
+        format!("{}/{}", dir(), self.sha256)
+    }
+
+    // Return the contents
+    pub fn get_contents(&self) -> Vec<u8> {
+        match fs::read(self.path()) {
+            Ok(contents) => contents,
+            Err(error) => {
+                panic!("Error reading attachment {}\n{}",
+                       self.path(), error)
+            },
+        }
+    }
+
+    // Associated functions
+
+    // Create an Attachment named name holding contents, storing the contents
+    // unless already stored.
+    pub fn store(name: &str, contents: &[u8]) -> Attachment {
+        let attachment = Attachment {
+            name: name.to_string(),
+            size: contents.len() as u64,
+            mime: mime_guess::from_path(name).first_raw().unwrap_or(DEFAULT_MIME).to_string(),
+            sha256: format!("{:x}", Sha256::digest(contents)),
+        };
+
+        if !std::path::Path::new(&attachment.path()).exists() {
+            crate::helpers::create_dir_all(&dir());
+            match fs::write(attachment.path(), contents) {
+                Ok(_) => (),
+                Err(error) => {
+                    panic!("Error writing attachment {}\n{}",
+                           attachment.path(), error)
+                },
+            };
+        }
+
+        attachment
+    }
+}
+
+// Return the directory holding the attachment contents
+pub fn dir() -> String {
+    format!("{}/attachments", &CONFIG.data)
+}
+
+// Remove the contents of attachment unless still used by a Tip in tips or in
+// the trash
+pub fn remove_unused(attachment: &Attachment, tips: &crate::tips::Tips) {
+    let trash = crate::trash::Trash::load();
+    let used = tips.tips.iter()
+        .chain(trash.tips.iter().map(|trashed| &trashed.tip))
+        .flat_map(|tip| tip.attachments.iter())
+        .any(|other| other.sha256 == attachment.sha256);
+
+    if !used && std::path::Path::new(&attachment.path()).exists() {
+        crate::helpers::remove_file(&attachment.path());
+    }
+}
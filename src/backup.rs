@@ -0,0 +1,188 @@
+// This file holds definition of the backup document, written by "tips export
+// json" and read by "tips import json".
+//
+// The document is self-contained: besides the metadata it holds the contents
+// of the data and parts inline, and the contents of the attachments base64
+// encoded. The uuids are kept, so a restored Tip is the same Tip. Tags are
+// null for a Tip without tags, as opposed to an empty list:
+//
+//      {
+//        "version": 1,
+//        "last_id": 14,
+//        "aliases": { "3": 2 },
+//        "tips": [
+//          {
+//            "id": 1,
+//            "uuid": "0b0e2c52-...",
+//            "subject": "My first tip",
+//            "tags": ["tip"],
+//            "extension": "txt",
+//            "created": "2019-05-01T10:00:00+02:00",
+//            "last_updated": null,
+//            "content": "...",
+//            "parts": [
+//              { "name": "manifest", "extension": "yaml", "uuid": "5f1d7a3e-...", "content": "..." }
+//            ],
+//            "attachments": [
+//              { "name": "screenshot.png", "mime": "image/png", "sha256": "9f86d081...", "content": "iVBORw0..." }
+//            ]
+//          }
+//        ]
+//      }
+
+use crate::statics::{CONFIG};
+use chrono::DateTime;
+use chrono::offset::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap};
+
+
+// Version of the backup document layout described above
+pub const BACKUP_VERSION: usize = 1;
+
+// Struct holding the backup document
+#[derive(Serialize, Deserialize)]
+pub struct Backup {
+    pub version: usize,
+    pub last_id: usize,
+    #[serde(default)]
+    pub aliases: BTreeMap<usize, usize>,
+    pub tips: Vec<BackupTip>,
+}
+
+// Struct holding a Tip in the backup document
+#[derive(Serialize, Deserialize)]
+pub struct BackupTip {
+    pub id: usize,
+    pub uuid: uuid::Uuid,
+    pub subject: String,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    pub extension: Option<String>,
+    pub created: Option<DateTime<Local>>,
+    pub last_updated: Option<DateTime<Local>>,
+    pub content: String,
+    #[serde(default)]
+    pub parts: Vec<BackupPart>,
+    #[serde(default)]
+    pub attachments: Vec<BackupAttachment>,
+}
+
+// Struct holding a part of a Tip in the backup document
+#[derive(Serialize, Deserialize)]
+pub struct BackupPart {
+    pub name: String,
+    pub extension: Option<String>,
+    pub uuid: uuid::Uuid,
+    pub content: String,
+}
+
+// Struct holding an attachment of a Tip in the backup document, with the
+// contents base64 encoded
+#[derive(Serialize, Deserialize)]
+pub struct BackupAttachment {
+    pub name: String,
+    pub mime: String,
+    pub sha256: String,
+    pub content: String,
+}
+
+impl Backup {
+
+    // Associated functions
+
+    // Create the backup document of tips
+    pub fn of(tips: &crate::tips::Tips) -> Backup {
+        Backup {
+            version: BACKUP_VERSION,
+            last_id: tips.highest_id(),
+            aliases: tips.aliases.clone(),
+            tips: tips.tips.iter().map(BackupTip::of).collect(),
+        }
+    }
+}
+
+impl BackupTip {
+
+    // Functions
+
+    // Create the Tip, writing its data, part and attachment files. The
+    // attachment contents are stored by their hash, so a changed attachment
+    // gets the hash of its contents.
+    pub fn restore(&self) -> crate::tip::Tip {
+        crate::helpers::write_to_file(&format!("{}/{}", &CONFIG.data, self.uuid), &self.content);
+
+        let parts = self.parts.iter()
+            .map(|part| {
+                let restored = crate::part::Part {
+                    name: part.name.to_string(),
+                    extension: part.extension.clone(),
+                    data: part.uuid,
+                };
+                crate::helpers::write_to_file(&restored.path(), &part.content);
+                restored
+            })
+            .collect();
+
+        let attachments = self.attachments.iter()
+            .map(|attachment| {
+                let contents = match base64::decode(&attachment.content) {
+                    Ok(contents) => contents,
+                    Err(error) => {
+                        panic!("Error decoding attachment {} of tip {}\n{}",
+                               attachment.name, self.id, error)
+                    },
+                };
+                let mut restored = crate::attachment::Attachment::store(&attachment.name, &contents);
+                restored.mime = attachment.mime.to_string();
+                restored
+            })
+            .collect();
+
+        crate::tip::Tip {
+            metadata: crate::metadata::Metadata {
+                subject: self.subject.to_string(),
+                id: Some(self.id),
+                tags: self.tags.clone(),
+                created: self.created,
+                last_updated: self.last_updated,
+                data_extension: self.extension.clone(),
+            },
+            data: self.uuid,
+            parts,
+            attachments,
+        }
+    }
+
+    // Associated functions
+
+    // Create the backup of tip, reading all its contents
+    fn of(tip: &crate::tip::Tip) -> BackupTip {
+        BackupTip {
+            id: tip.metadata.id.unwrap_or(0),
+            uuid: tip.data,
+            subject: tip.metadata.subject.to_string(),
+            tags: tip.metadata.tags.clone(),
+            extension: tip.metadata.data_extension.clone(),
+            created: tip.metadata.created,
+            last_updated: tip.metadata.last_updated,
+            content: tip.get_data(),
+            parts: tip.parts.iter()
+                .map(|part| BackupPart {
+                    name: part.name.to_string(),
+                    extension: part.extension.clone(),
+                    uuid: part.data,
+                    content: part.get_data(),
+                })
+                .collect(),
+            attachments: tip.attachments.iter()
+                .map(|attachment| BackupAttachment {
+                    name: attachment.name.to_string(),
+                    mime: attachment.mime.to_string(),
+                    sha256: attachment.sha256.to_string(),
+                    content: base64::encode(attachment.get_contents()),
+                })
+                .collect(),
+        }
+    }
+}
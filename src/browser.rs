@@ -0,0 +1,522 @@
+// This file contains functionality for the interactive terminal browser
+// (tui subcommand).
+//
+// The screen is split in a filterable list of tips on the left, and a syntax
+// highlighted preview of the selected tip on the right. The bottom line shows
+// the filter, prompts and messages.
+
+use crate::filter::{Entry, Matching};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::{execute, terminal};
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Style, ThemeSet};
+use syntect::parsing::SyntaxSet;
+use syntect::util::{LinesWithEndings};
+use tui::backend::{CrosstermBackend};
+use tui::layout::{Constraint, Direction, Layout};
+use tui::style::{Color, Modifier};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use std::{io, panic};
+
+
+// Help shown in the status line
+const HELP: &str = "q quit  / filter  Tab fuzzy/regex  e edit  d delete  t tags  y copy  n new";
+
+// Number of lines scrolled in the preview by one key press
+const SCROLL_LINES: u16 = 10;
+
+type Terminal = tui::Terminal<CrosstermBackend<io::Stdout>>;
+
+// Enum describing what key presses are used for
+enum Mode {
+
+    // Navigate the list and run actions
+    Normal,
+
+    // Edit the filter query
+    Filter,
+
+    // Edit the tags of the selected Tip
+    Retag(String),
+
+    // Confirm removal of the selected Tip
+    ConfirmDelete,
+}
+
+// Enum describing the action to take after a key press
+enum Action {
+
+    // Continue browsing
+    None,
+
+    // Leave the browser
+    Quit,
+
+    // Edit the Tip at index in the tips in the editor
+    Edit(usize),
+
+    // Create a new Tip in the editor
+    Create,
+}
+
+// Struct holding the state of the browser
+struct Browser {
+
+    // All tips and the text used to filter them
+    tips: crate::tips::Tips,
+    entries: Vec<Entry>,
+
+    // The filter query and how it is matched
+    query: String,
+    matching: Matching,
+
+    // Indexes (in tips) of the tips matching the filter, in list order
+    visible: Vec<usize>,
+
+    // Selection in the list of visible tips
+    state: ListState,
+
+    // What key presses are used for
+    mode: Mode,
+
+    // Message shown in the status line
+    message: String,
+
+    // Preview of the selected Tip (index in tips and lines) and its scroll
+    preview: Option<(usize, Vec<Spans<'static>>)>,
+    scroll: u16,
+
+    // Syntaxes and themes used for the preview
+    ps: SyntaxSet,
+    ts: ThemeSet,
+}
+
+// Entry point for tui subcommand
+pub fn browser(_matches: &clap::ArgMatches) {
+    let mut browser = Browser::new();
+
+    // Restore the terminal before a panic message is printed
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        leave();
+        default_hook(info);
+    }));
+
+    let mut terminal = enter();
+    loop {
+        if let Err(error) = terminal.draw(|f| browser.draw(f)) {
+            leave();
+            panic!("Error drawing browser\n{}", error);
+        }
+
+        let key = match event::read() {
+            Ok(Event::Key(key)) => key,
+            Ok(_)               => continue,
+            Err(error) => {
+                leave();
+                panic!("Error reading terminal event\n{}", error);
+            },
+        };
+
+        match browser.key(key) {
+            Action::None        => (),
+            Action::Quit        => break,
+            Action::Edit(index) => {
+                leave();
+                let id = browser.tips.tips[index].metadata.id;
+                crate::update::update_tip(index);
+                terminal = enter();
+                browser.reload(id);
+            },
+            Action::Create      => {
+                leave();
+                let id = crate::add::interactive();
+                terminal = enter();
+                browser.reload(id);
+            },
+        }
+    }
+
+    leave();
+}
+
+// Switch the terminal to raw mode on the alternate screen
+fn enter() -> Terminal {
+    let setup = terminal::enable_raw_mode()
+        .and_then(|_| execute!(io::stdout(), terminal::EnterAlternateScreen));
+    if let Err(error) = setup {
+        panic!("Error setting up terminal\n{}", error);
+    }
+
+    match Terminal::new(CrosstermBackend::new(io::stdout())) {
+        Ok(terminal) => terminal,
+        Err(error) => {
+            leave();
+            panic!("Error creating terminal\n{}", error);
+        },
+    }
+}
+
+// Restore the terminal, errors are ignored since this is also used while
+// handling errors.
+fn leave() {
+    let _ = terminal::disable_raw_mode();
+    let _ = execute!(io::stdout(), terminal::LeaveAlternateScreen, crossterm::cursor::Show);
+}
+
+impl Browser {
+
+    // Functions
+
+    // Handle a key press and return the action to take
+    fn key(&mut self, key: KeyEvent) -> Action {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Action::Quit;
+        }
+
+        match self.mode {
+            Mode::Normal        => self.key_normal(key),
+            Mode::Filter        => self.key_filter(key),
+            Mode::Retag(_)      => self.key_retag(key),
+            Mode::ConfirmDelete => self.key_delete(key),
+        }
+    }
+
+    // Handle a key press in normal mode
+    fn key_normal(&mut self, key: KeyEvent) -> Action {
+        self.message.clear();
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc   => return Action::Quit,
+            KeyCode::Char('d') if ctrl          => self.scroll_preview(true),
+            KeyCode::Char('u') if ctrl          => self.scroll_preview(false),
+            KeyCode::Char('J')                  => self.scroll_preview(true),
+            KeyCode::Char('K')                  => self.scroll_preview(false),
+            KeyCode::Up | KeyCode::Char('k')    => self.select_offset(-1),
+            KeyCode::Down | KeyCode::Char('j')  => self.select_offset(1),
+            KeyCode::PageUp                     => self.select_offset(-(SCROLL_LINES as isize)),
+            KeyCode::PageDown                   => self.select_offset(SCROLL_LINES as isize),
+            KeyCode::Home | KeyCode::Char('g')  => self.select(0),
+            KeyCode::End | KeyCode::Char('G')   => self.select(self.visible.len().saturating_sub(1)),
+            KeyCode::Char('/')                  => self.mode = Mode::Filter,
+            KeyCode::Tab                        => self.toggle_matching(),
+            KeyCode::Char('n')                  => return Action::Create,
+            KeyCode::Char('e') | KeyCode::Enter => {
+                if let Some(index) = self.selected_index() {
+                    return Action::Edit(index);
+                }
+            },
+            KeyCode::Char('d') if self.selected().is_some() => {
+                self.mode = Mode::ConfirmDelete;
+            },
+            KeyCode::Char('t') => {
+                if let Some(tip) = self.selected() {
+                    let tags = tip.metadata.tags.clone().unwrap_or_default().join(" ");
+                    self.mode = Mode::Retag(tags);
+                }
+            },
+            KeyCode::Char('y') => {
+                if let Some(tip) = self.selected() {
+                    let method = crate::clipboard::copy(&tip.get_data());
+                    self.message = format!("Copied tip {} ({})", tip.metadata.id.unwrap(), method);
+                }
+            },
+            _ => (),
+        };
+
+        Action::None
+    }
+
+    // Handle a key press while editing the filter query
+    fn key_filter(&mut self, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Enter | KeyCode::Esc => self.mode = Mode::Normal,
+            KeyCode::Up                   => self.select_offset(-1),
+            KeyCode::Down                 => self.select_offset(1),
+            KeyCode::Tab                  => self.toggle_matching(),
+            KeyCode::Backspace            => {
+                self.query.pop();
+                self.refilter();
+            },
+            KeyCode::Char(c)              => {
+                self.query.push(c);
+                self.refilter();
+            },
+            _ => (),
+        };
+
+        Action::None
+    }
+
+    // Handle a key press while editing tags
+    fn key_retag(&mut self, key: KeyEvent) -> Action {
+        let mut tags = match &self.mode {
+            Mode::Retag(tags) => tags.to_string(),
+            _                 => return Action::None,
+        };
+
+        match key.code {
+            KeyCode::Esc       => self.mode = Mode::Normal,
+            KeyCode::Enter     => {
+                self.mode = Mode::Normal;
+                self.retag(&tags);
+            },
+            KeyCode::Backspace => {
+                tags.pop();
+                self.mode = Mode::Retag(tags);
+            },
+            KeyCode::Char(c)   => {
+                tags.push(c);
+                self.mode = Mode::Retag(tags);
+            },
+            _ => (),
+        };
+
+        Action::None
+    }
+
+    // Handle a key press while confirming removal
+    fn key_delete(&mut self, key: KeyEvent) -> Action {
+        self.mode = Mode::Normal;
+
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                if let Some(index) = self.selected_index() {
+                    let id = self.tips.tips[index].metadata.id.unwrap();
+                    crate::remove::remove_index(&mut self.tips, index);
+                    self.reload(None);
+                    self.message = format!("Moved tip {} to trash", id);
+                }
+            },
+            _ => self.message = "Aborted by user".to_string(),
+        };
+
+        Action::None
+    }
+
+    // Set the tags of the selected Tip, space separated, and store tips
+    fn retag(&mut self, tags: &str) {
+        let index = match self.selected_index() {
+            Some(index) => index,
+            None        => return,
+        };
+
+        let tags: Vec<String> = tags.split_whitespace().map(|t| t.to_string()).collect();
+        let tip = &mut self.tips.tips[index];
+        crate::revision::ensure(tip);
+        tip.metadata.tags = if tags.is_empty() { None } else { Some(tags) };
+        tip.metadata.last_updated = Some(chrono::offset::Local::now());
+        crate::revision::record(tip, "tags updated");
+        let id = tip.metadata.id;
+        let message = crate::git::message("tag", tip);
+
+        self.tips.store();
+        crate::git::commit(&message);
+        self.reload(id);
+        self.message = "Tags updated".to_string();
+    }
+
+    // Toggle between fuzzy and regex matching
+    fn toggle_matching(&mut self) {
+        self.matching = self.matching.toggle();
+        self.refilter();
+    }
+
+    // Update the visible tips given the filter query
+    fn refilter(&mut self) {
+        match crate::filter::filter(&self.entries, &self.query, self.matching) {
+            Ok(visible) => {
+                self.visible = visible;
+                self.message.clear();
+            },
+            Err(message) => self.message = message,
+        };
+        self.select(0);
+    }
+
+    // Load tips from the database again, selecting the Tip with id if given
+    fn reload(&mut self, id: Option<usize>) {
+        self.tips = crate::tips::Tips::load();
+        self.entries = self.tips.tips.iter().map(|tip| Entry::new(tip, true)).collect();
+        self.preview = None;
+        self.refilter();
+
+        if let Some(id) = id {
+            if let Some(position) = self.visible.iter()
+                .position(|index| self.tips.tips[*index].metadata.id == Some(id)) {
+                self.select(position);
+            }
+        }
+    }
+
+    // Select the visible Tip at position
+    fn select(&mut self, position: usize) {
+        if self.visible.is_empty() {
+            self.state.select(None);
+        } else {
+            self.state.select(Some(position.min(self.visible.len() - 1)));
+        }
+        self.scroll = 0;
+    }
+
+    // Move the selection by offset
+    fn select_offset(&mut self, offset: isize) {
+        let current = self.state.selected().unwrap_or(0) as isize;
+        self.select((current + offset).max(0) as usize);
+    }
+
+    // Scroll the preview down or up
+    fn scroll_preview(&mut self, down: bool) {
+        self.scroll = if down {
+            self.scroll.saturating_add(SCROLL_LINES)
+        } else {
+            self.scroll.saturating_sub(SCROLL_LINES)
+        };
+    }
+
+    // Index in tips of the selected Tip
+    fn selected_index(&self) -> Option<usize> {
+        self.state.selected().and_then(|position| self.visible.get(position).copied())
+    }
+
+    // The selected Tip
+    fn selected(&self) -> Option<&crate::tip::Tip> {
+        self.selected_index().map(|index| &self.tips.tips[index])
+    }
+
+    // Draw the browser
+    fn draw(&mut self, f: &mut tui::Frame<CrosstermBackend<io::Stdout>>) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)].as_ref())
+            .split(f.size());
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(35), Constraint::Percentage(65)].as_ref())
+            .split(rows[0]);
+
+        // The list of visible tips
+        let items: Vec<ListItem> = self.visible.iter().map(|index| {
+            let tip = &self.tips.tips[*index];
+            let tags = tip.metadata.tags.clone().unwrap_or_default().join(" ");
+            ListItem::new(Spans::from(vec![
+                Span::styled(format!("{:>3} ", tip.metadata.id.unwrap_or(0)),
+                             tui::style::Style::default().fg(Color::DarkGray)),
+                Span::raw(tip.metadata.subject.to_string()),
+                Span::styled(format!(" {}", tags),
+                             tui::style::Style::default().fg(Color::Cyan)),
+            ]))
+        }).collect();
+
+        let title = format!(" Tips {}/{} [{}] ",
+                            self.visible.len(), self.tips.tips.len(), self.matching.name());
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(tui::style::Style::default().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(list, columns[0], &mut self.state);
+
+        // The preview of the selected Tip
+        let (title, lines) = match self.selected_index() {
+            Some(index) => {
+                let tip = &self.tips.tips[index];
+                let title = format!(" {} ", tip.metadata.subject);
+                (title, self.preview(index))
+            },
+            None => (" No tips ".to_string(), Vec::new()),
+        };
+        let preview = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .scroll((self.scroll, 0));
+        f.render_widget(preview, columns[1]);
+
+        // The status line
+        let status = match &self.mode {
+            Mode::Filter        => format!("/{}", self.query),
+            Mode::Retag(tags)   => format!("tags: {}", tags),
+            Mode::ConfirmDelete => "Sure you want to move tip to trash ? [y/n]".to_string(),
+            Mode::Normal if !self.message.is_empty() => self.message.to_string(),
+            Mode::Normal if !self.query.is_empty()   => format!("/{}   {}", self.query, HELP),
+            Mode::Normal        => HELP.to_string(),
+        };
+        f.render_widget(Paragraph::new(status), rows[1]);
+
+        match &self.mode {
+            Mode::Filter      => f.set_cursor(rows[1].x + 1 + self.query.chars().count() as u16, rows[1].y),
+            Mode::Retag(tags) => f.set_cursor(rows[1].x + 6 + tags.chars().count() as u16, rows[1].y),
+            _ => (),
+        };
+    }
+
+    // The highlighted lines of the Tip at index, cached until another Tip
+    // is previewed.
+    fn preview(&mut self, index: usize) -> Vec<Spans<'static>> {
+        if let Some((cached, lines)) = &self.preview {
+            if *cached == index {
+                return lines.clone();
+            }
+        }
+
+        let tip = &self.tips.tips[index];
+        let data = self.entries[index].data.replace('\t', "    ");
+        let ext = match &tip.metadata.data_extension {
+            Some(ext) if !ext.trim().is_empty() => ext.to_string(),
+            _ => crate::detect::extension(&data, &self.ps).unwrap_or_else(|| "txt".to_string()),
+        };
+        let syntax = match self.ps.find_syntax_by_extension(&ext) {
+            Some(syntax) => syntax,
+            None         => self.ps.find_syntax_plain_text(),
+        };
+
+        let depth = crate::color::depth();
+        let mut h = HighlightLines::new(syntax, crate::assets::theme(&self.ts));
+        let lines: Vec<Spans<'static>> = LinesWithEndings::from(&data).map(|line| {
+            let ranges: Vec<(Style, &str)> = h.highlight(line, &self.ps);
+            Spans::from(ranges.iter().map(|(style, text)| {
+                Span::styled(
+                    text.trim_end_matches('\n').to_string(),
+                    tui::style::Style::default().fg(color(style.foreground, depth)))
+            }).collect::<Vec<Span>>())
+        }).collect();
+
+        self.preview = Some((index, lines.clone()));
+        lines
+    }
+
+    // Associated functions
+
+    // Create the browser showing all tips
+    fn new() -> Browser {
+        let tips = crate::tips::Tips::load();
+        let entries: Vec<Entry> = tips.tips.iter().map(|tip| Entry::new(tip, true)).collect();
+        let visible = (0..entries.len()).collect();
+
+        let mut browser = Browser {
+            tips,
+            entries,
+            query: String::new(),
+            matching: Matching::Fuzzy,
+            visible,
+            state: ListState::default(),
+            mode: Mode::Normal,
+            message: String::new(),
+            preview: None,
+            scroll: 0,
+            ps: crate::assets::syntax_set(),
+            ts: crate::assets::theme_set(),
+        };
+        browser.select(0);
+        browser
+    }
+}
+
+// Convert a syntect color to a terminal color of the color depth
+fn color(color: syntect::highlighting::Color, depth: crate::color::Depth) -> Color {
+    match depth {
+        crate::color::Depth::TrueColor => Color::Rgb(color.r, color.g, color.b),
+        crate::color::Depth::Ansi256   => Color::Indexed(crate::color::ansi256(color)),
+        crate::color::Depth::Ansi16    => Color::Indexed(crate::color::ansi16(color)),
+        crate::color::Depth::None      => Color::Reset,
+    }
+}
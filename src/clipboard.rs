@@ -0,0 +1,61 @@
+// This file contains functionality to copy text to the system clipboard
+
+use std::io::{Write};
+use std::{io, process};
+
+
+// Clipboard commands tried in order, the first one that succeeds is used
+const COMMANDS: &[&[&str]] = &[
+    &["wl-copy"],
+    &["xclip", "-selection", "clipboard"],
+    &["xsel", "--clipboard", "--input"],
+    &["pbcopy"],
+];
+
+// Copy text to the clipboard, returning the name of the method used. If no
+// clipboard command is available the OSC 52 terminal escape is written,
+// which most terminal emulators (also over ssh) forward to the clipboard.
+pub fn copy(text: &str) -> &'static str {
+    for command in COMMANDS {
+        if run(command, text) {
+            return command[0];
+        }
+    }
+
+    let escape = format!("\x1b]52;c;{}\x07", base64::encode(text));
+    let mut stdout = io::stdout();
+    match stdout.write_all(escape.as_bytes()).and_then(|_| stdout.flush()) {
+        Ok(_) => (),
+        Err(error) => {
+            panic!("Error when trying to write to stdout\n{}", error)
+        },
+    };
+
+    "OSC 52"
+}
+
+// Run the clipboard command with text on stdin, true if it succeeded
+fn run(command: &[&str], text: &str) -> bool {
+    let child = process::Command::new(command[0])
+        .args(&command[1..])
+        .stdin(process::Stdio::piped())
+        .stdout(process::Stdio::null())
+        .stderr(process::Stdio::null())
+        .spawn();
+
+    let mut child = match child {
+        Ok(child) => child,
+        Err(_)    => return false,
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        if stdin.write_all(text.as_bytes()).is_err() {
+            return false;
+        }
+    }
+
+    match child.wait() {
+        Ok(status) => status.success(),
+        Err(_)     => false,
+    }
+}
@@ -0,0 +1,186 @@
+// This file contains functionality to decide if and how colors are used.
+//
+// Colors are controlled by the global --color argument:
+//      always, colors are always used
+//      never, colors are never used
+//      auto, colors are used when stdout is a terminal, unless NO_COLOR is
+//      set. CLICOLOR_FORCE (set and not 0) enables colors also when stdout
+//      is not a terminal.
+//
+// The color depth is truecolor if COLORTERM is truecolor or 24bit, 256 colors
+// if TERM contains 256color, otherwise 16 colors. Syntect colors are
+// downsampled to the depth of the terminal.
+
+use syntect::highlighting::{Color, Style};
+use std::{env, sync};
+
+
+// Values accepted by the --color argument
+pub static CHOICES: &[&str] = &["auto", "always", "never"];
+
+// The --color choice, set once by init
+static CHOICE: sync::OnceLock<Choice> = sync::OnceLock::new();
+
+// Enum describing the --color choice
+#[derive(Clone, Copy)]
+enum Choice {
+    Auto,
+    Always,
+    Never,
+}
+
+// Enum describing the number of colors supported by the terminal
+#[derive(Clone, Copy, PartialEq)]
+pub enum Depth {
+
+    // Colors are not used
+    None,
+
+    // The 16 standard ANSI colors
+    Ansi16,
+
+    // The 256 color palette
+    Ansi256,
+
+    // 24 bit colors
+    TrueColor,
+}
+
+// The standard 16 ANSI colors, as commonly rendered by terminals
+const ANSI16: [(u8, u8, u8); 16] = [
+    (0, 0, 0), (205, 0, 0), (0, 205, 0), (205, 205, 0),
+    (0, 0, 238), (205, 0, 205), (0, 205, 205), (229, 229, 229),
+    (127, 127, 127), (255, 0, 0), (0, 255, 0), (255, 255, 0),
+    (92, 92, 255), (255, 0, 255), (0, 255, 255), (255, 255, 255),
+];
+
+// Set the --color choice given the arguments. The argument is global, so it
+// may be given either before or after the subcommand.
+pub fn init(matches: &clap::ArgMatches) {
+    let value = match matches.subcommand() {
+        (_, Some(sub)) if sub.occurrences_of("color") > 0 => sub.value_of("color"),
+        _ => matches.value_of("color"),
+    };
+
+    let choice = match value {
+        Some("always") => Choice::Always,
+        Some("never")  => Choice::Never,
+        _              => Choice::Auto,
+    };
+
+    let _ = CHOICE.set(choice);
+}
+
+// Return true if colors shall be used
+pub fn enabled() -> bool {
+    match CHOICE.get().copied().unwrap_or(Choice::Auto) {
+        Choice::Always => true,
+        Choice::Never  => false,
+        Choice::Auto   => {
+            if env::var_os("NO_COLOR").map(|v| !v.is_empty()).unwrap_or(false) {
+                false
+            } else if env::var("CLICOLOR_FORCE").map(|v| v != "0").unwrap_or(false) {
+                true
+            } else {
+                atty::is(atty::Stream::Stdout)
+            }
+        },
+    }
+}
+
+// Return the color depth to use
+pub fn depth() -> Depth {
+    if !enabled() {
+        return Depth::None;
+    }
+
+    match env::var("COLORTERM") {
+        Ok(ref ct) if ct == "truecolor" || ct == "24bit" => return Depth::TrueColor,
+        _ => (),
+    };
+
+    match env::var("TERM") {
+        Ok(ref term) if term.contains("256color") => Depth::Ansi256,
+        _                                         => Depth::Ansi16,
+    }
+}
+
+// Return the ranges highlighted by syntect as a String with terminal escapes
+// for the foreground colors, downsampled to the color depth.
+pub fn escaped(ranges: &[(Style, &str)]) -> String {
+    let depth = depth();
+    let mut output = String::new();
+
+    for (style, text) in ranges {
+        output.push_str(&foreground(style.foreground, depth));
+        output.push_str(text);
+    }
+
+    output
+}
+
+// Return the escape sequence setting the foreground color
+pub fn foreground(color: Color, depth: Depth) -> String {
+    match depth {
+        Depth::None      => String::new(),
+        Depth::TrueColor => format!("\x1b[38;2;{};{};{}m", color.r, color.g, color.b),
+        Depth::Ansi256   => format!("\x1b[38;5;{}m", ansi256(color)),
+        Depth::Ansi16    => {
+            let index = ansi16(color);
+            if index < 8 {
+                format!("\x1b[{}m", 30 + index)
+            } else {
+                format!("\x1b[{}m", 90 + index - 8)
+            }
+        },
+    }
+}
+
+// Map a color to the closest color in the 256 color palette, using either
+// the 6x6x6 color cube or the grayscale ramp.
+pub fn ansi256(color: Color) -> u8 {
+    let cube = |c: u8| -> u8 {
+        if c < 48 {
+            0
+        } else if c < 115 {
+            1
+        } else {
+            (c - 35) / 40
+        }
+    };
+    let level = |i: u8| -> u8 { if i == 0 { 0 } else { 55 + i * 40 } };
+
+    let (r, g, b) = (cube(color.r), cube(color.g), cube(color.b));
+    let cube_index = 16 + 36 * r + 6 * g + b;
+    let cube_color = (level(r), level(g), level(b));
+
+    let average = (color.r as u16 + color.g as u16 + color.b as u16) / 3;
+    let gray_index = if average > 238 { 23 } else { (average.saturating_sub(3) / 10) as u8 };
+    let gray = 8 + gray_index * 10;
+    let gray_color = (gray, gray, gray);
+
+    let rgb = (color.r, color.g, color.b);
+    if distance(rgb, gray_color) < distance(rgb, cube_color) {
+        232 + gray_index
+    } else {
+        cube_index
+    }
+}
+
+// Map a color to the index of the closest of the 16 standard colors
+pub fn ansi16(color: Color) -> u8 {
+    let rgb = (color.r, color.g, color.b);
+    let mut best = 0;
+    for (index, candidate) in ANSI16.iter().enumerate() {
+        if distance(rgb, *candidate) < distance(rgb, ANSI16[best]) {
+            best = index;
+        }
+    }
+    best as u8
+}
+
+// Squared distance between two colors
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
@@ -0,0 +1,265 @@
+// This file contains functionality to select, size and style the columns
+// used when presenting tips in a table.
+
+use crate::statics::{CONFIG};
+use prettytable::format::{Alignment};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+
+// Values accepted in the --columns argument and list.columns config
+pub static COLUMNS: &[&str] = &[
+    "id", "subject", "tags", "created", "updated", "ext", "size", "uuid"];
+
+// Truncation never makes a column narrower than this
+const MIN_WIDTH: usize = 8;
+
+// Enum describing a column of the table
+pub enum Column {
+
+    // The tip's id
+    Id,
+
+    // The tip's subject
+    Subject,
+
+    // The tip's tags separated by space
+    Tags,
+
+    // Date and time when the tip was created
+    Created,
+
+    // Date and time when the tip was last updated
+    Updated,
+
+    // The tip's data extension
+    Ext,
+
+    // Size of the tip's data file
+    Size,
+
+    // The first characters of the tip's uuid
+    Uuid,
+}
+
+impl Column {
+
+    // Functions
+
+    // Name of the column as used in the configuration
+    fn name(&self) -> &'static str {
+        match self {
+            Column::Id      => "id",
+            Column::Subject => "subject",
+            Column::Tags    => "tags",
+            Column::Created => "created",
+            Column::Updated => "updated",
+            Column::Ext     => "ext",
+            Column::Size    => "size",
+            Column::Uuid    => "uuid",
+        }
+    }
+
+    // The text shown in this column for given Tip
+    pub fn text(&self, tip: &crate::tip::Tip) -> String {
+        let datetime = |dt: Option<chrono::DateTime<chrono::offset::Local>>| {
+            match dt {
+                Some(dt) => dt.format("%Y-%m-%d %H:%M").to_string(),
+                None     => String::new(),
+            }
+        };
+
+        match self {
+            Column::Id      => format!("{}", tip.metadata.id.unwrap_or(0)),
+            Column::Subject => tip.metadata.subject.to_string(),
+            Column::Tags    => match &tip.metadata.tags {
+                Some(tags) => tags.join(" "),
+                None       => String::new(),
+            },
+            Column::Created => datetime(tip.metadata.created),
+            Column::Updated => datetime(tip.metadata.last_updated),
+            Column::Ext     => tip.metadata.data_extension.clone().unwrap_or_default(),
+            Column::Size    => human_size(tip.data_size()),
+            Column::Uuid    => tip.short_uuid(),
+        }
+    }
+
+    // The prettytable style spec for this column
+    fn style(&self) -> &str {
+        let table = &CONFIG.style.table;
+        match self {
+            Column::Id      => &table.id,
+            Column::Subject => &table.subject,
+            Column::Tags    => &table.tags,
+            Column::Created => &table.created,
+            Column::Updated => &table.updated,
+            Column::Ext     => &table.ext,
+            Column::Size    => &table.size,
+            Column::Uuid    => &table.uuid,
+        }
+    }
+
+    // The alignment for this column, configured or default
+    fn align(&self) -> Alignment {
+        match CONFIG.style.table.align.get(self.name()).map(|a| a.as_str()) {
+            Some("left")   => Alignment::LEFT,
+            Some("center") => Alignment::CENTER,
+            Some("right")  => Alignment::RIGHT,
+            _              => match self {
+                Column::Tags | Column::Size => Alignment::RIGHT,
+                _                           => Alignment::LEFT,
+            },
+        }
+    }
+
+    // Create the aligned Cell containing text, styled if colors are used
+    pub fn cell(&self, text: &str) -> prettytable::Cell {
+        let mut cell = prettytable::Cell::new(text);
+        if crate::color::enabled() {
+            cell = cell.style_spec(self.style());
+        }
+        cell.align(self.align());
+        cell
+    }
+
+    // Associated functions
+
+    // Get the Column given its name
+    pub fn from_name(name: &str) -> Option<Column> {
+        match name.trim() {
+            "id"      => Some(Column::Id),
+            "subject" => Some(Column::Subject),
+            "tags"    => Some(Column::Tags),
+            "created" => Some(Column::Created),
+            "updated" => Some(Column::Updated),
+            "ext"     => Some(Column::Ext),
+            "size"    => Some(Column::Size),
+            "uuid"    => Some(Column::Uuid),
+            _         => None,
+        }
+    }
+}
+
+// Parse a comma separated list of column names. Panics on unknown names.
+pub fn parse(columns: &str) -> Vec<Column> {
+    columns.split(',').map(|name| {
+        match Column::from_name(name) {
+            Some(column) => column,
+            None => panic!("Unknown column '{}', expected one of: {}",
+                           name, COLUMNS.join(",")),
+        }
+    }).collect()
+}
+
+// The columns from the list.columns configuration
+pub fn configured() -> Vec<Column> {
+    parse(&CONFIG.list.columns.join(","))
+}
+
+// The columns selected by the --columns argument, falling back to the
+// list.columns configuration.
+pub fn selected(matches: &clap::ArgMatches) -> Vec<Column> {
+    match matches.value_of("columns") {
+        Some(columns) => parse(columns),
+        None          => configured(),
+    }
+}
+
+// Create the table rows for the tips using given columns. When stdout is a
+// terminal the widest column is truncated (with an ellipsis) until the table
+// fits the terminal width.
+pub fn rows(tips: &[&crate::tip::Tip], columns: &[Column]) -> Vec<Vec<prettytable::Cell>> {
+    let mut texts: Vec<Vec<String>> = tips.iter()
+        .map(|tip| columns.iter().map(|column| column.text(tip)).collect())
+        .collect();
+
+    if let Some(width) = terminal_width() {
+        fit(&mut texts, columns.len(), width);
+    }
+
+    texts.iter()
+        .map(|row| columns.iter()
+             .zip(row.iter())
+             .map(|(column, text)| column.cell(text))
+             .collect())
+        .collect()
+}
+
+// Width of the terminal, None if stdout is not a terminal
+fn terminal_width() -> Option<usize> {
+    if !atty::is(atty::Stream::Stdout) {
+        return None;
+    }
+
+    terminal_size::terminal_size().map(|(width, _)| width.0 as usize)
+}
+
+// Truncate the widest column of texts until the table fits in width. The
+// columns are separated by one character (see present::tableformat).
+fn fit(texts: &mut [Vec<String>], num_columns: usize, width: usize) {
+    if num_columns == 0 {
+        return;
+    }
+
+    let widths: Vec<usize> = (0..num_columns)
+        .map(|col| texts.iter().map(|row| row[col].width()).max().unwrap_or(0))
+        .collect();
+
+    let overhead = num_columns - 1;
+    let total: usize = widths.iter().sum::<usize>() + overhead;
+    if total <= width {
+        return;
+    }
+
+    let (widest, widest_width) = match widths.iter().enumerate().max_by_key(|(_, w)| **w) {
+        Some((col, w)) => (col, *w),
+        None           => return,
+    };
+
+    let others = total - widest_width;
+    let target = width.saturating_sub(others).max(MIN_WIDTH);
+    if target >= widest_width {
+        return;
+    }
+
+    for row in texts.iter_mut() {
+        row[widest] = truncate(&row[widest], target);
+    }
+}
+
+// Truncate text to width (in terminal columns), ending with an ellipsis when
+// truncated.
+pub fn truncate(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+
+    let mut truncated = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w + 1 > width {
+            break;
+        }
+        truncated.push(c);
+        used += w;
+    }
+    truncated.push('…');
+    truncated
+}
+
+// Format a size in bytes for humans
+pub fn human_size(bytes: u64) -> String {
+    let units = ["B", "K", "M", "G"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < units.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{}{}", bytes, units[unit])
+    } else {
+        format!("{:.1}{}", size, units[unit])
+    }
+}
@@ -2,6 +2,8 @@
 // to load and also store this struct to file.
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path;
 
 // This struct represents the configuration of tips
 //
@@ -25,6 +27,25 @@ pub struct Config {
 
     // Color definitions
     pub style: Style,
+
+    // pager field holds the command used to page long output. When not set
+    // $PAGER is used, and an empty string disables paging.
+    #[serde(default)]
+    pub pager: Option<String>,
+
+    // Configuration for the list subcommand
+    #[serde(default)]
+    pub list: ListConfig,
+
+    // git field enables git mode, where every change is committed to a git
+    // repository in the tips home (see git.rs)
+    #[serde(default)]
+    pub git: bool,
+
+    // sync field is the remote used by the sync subcommand, a git repository
+    // or a directory path (see sync.rs)
+    #[serde(default)]
+    pub sync: Option<String>,
 }
 
 impl Config {
@@ -46,6 +67,17 @@ impl Config {
         crate::helpers::write_to_file(&file_path, &data);
     }
 
+    // Return the tips home directory, the directory of the database file
+    pub fn home(&self) -> path::PathBuf {
+        match path::Path::new(&self.db_file).parent() {
+            Some(home) => home.to_path_buf(),
+            None => {
+                panic!("Error unable to get directory of db_file {}",
+                       &self.db_file)
+            },
+        }
+    }
+
     // Associated functions
 
     // Load config from ~/.tipsrc file and deserialize it into a Config
@@ -88,6 +120,52 @@ pub struct TableStyle {
 
     // Style for the tags column
     pub tags: String,
+
+    // Style for the created column
+    #[serde(default)]
+    pub created: String,
+
+    // Style for the updated column
+    #[serde(default)]
+    pub updated: String,
+
+    // Style for the ext column
+    #[serde(default)]
+    pub ext: String,
+
+    // Style for the size column
+    #[serde(default)]
+    pub size: String,
+
+    // Style for the uuid column
+    #[serde(default)]
+    pub uuid: String,
+
+    // Alignment per column name, "left", "center" or "right". Columns not
+    // listed keep their default alignment.
+    #[serde(default)]
+    pub align: HashMap<String, String>,
+}
+
+
+// Struct that hold list configuration
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListConfig {
+
+    // Columns shown when listing tips, see crate::columns::COLUMNS
+    pub columns: Vec<String>,
+}
+
+impl Default for ListConfig {
+    fn default() -> ListConfig {
+        ListConfig {
+            columns: vec![
+                "id".to_string(),
+                "subject".to_string(),
+                "tags".to_string(),
+            ],
+        }
+    }
 }
 
 
@@ -97,6 +175,10 @@ pub struct DataStyle {
 
     // Set theme for data output
     pub theme: String,
+
+    // Data extensions rendered as Markdown by show, for example [md]
+    #[serde(default)]
+    pub render: Vec<String>,
 }
 
 
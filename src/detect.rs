@@ -0,0 +1,183 @@
+// This file contains functionality to detect the data extension of a tip
+// from its contents, used when data_extension is not set.
+//
+// Detection is tried in the following order:
+//      vim/emacs modelines, for example "# vim: ft=python" or "-*- mode: ruby -*-"
+//      shebang lines, for example "#!/usr/bin/env python3"
+//      syntect's first line matching, for example "<?xml" or "<?php"
+//      a heuristic classifier scoring common language constructs
+
+use syntect::parsing::SyntaxSet;
+
+
+// Number of lines searched for modelines at the start and end of the data
+const MODELINE_LINES: usize = 5;
+
+// A heuristic classifier never picks a language scoring less than this
+const MIN_SCORE: usize = 3;
+
+lazy_static::lazy_static! {
+
+    // Regex matching vim modelines, capturing the filetype
+    static ref VIM: regex::Regex = regex::Regex::new(
+        r"(?:vim?|ex):.*\b(?:ft|filetype|syntax)=([A-Za-z0-9_+-]+)").unwrap();
+
+    // Regex matching emacs modelines, capturing the mode
+    static ref EMACS: regex::Regex = regex::Regex::new(
+        r"-\*-\s*(?:.*mode:\s*([A-Za-z0-9_+-]+).*|([A-Za-z0-9_+-]+))\s*-\*-").unwrap();
+
+    // Regex matching shebang lines, capturing the interpreter
+    static ref SHEBANG: regex::Regex = regex::Regex::new(
+        r"^#!\s*\S*?/?(?:env\s+(?:-\S+\s+)*)?([A-Za-z_+-]+)[0-9.]*(?:\s|$)").unwrap();
+
+    // Patterns and their weight for each extension in the heuristic classifier
+    static ref HEURISTICS: Vec<(&'static str, Vec<(regex::Regex, usize)>)> = vec![
+        ("rs", patterns(&[
+            (r"\bfn\s+\w+\s*[<(]", 3), (r"\blet\s+mut\b", 3), (r"\bimpl\b", 2),
+            (r"\w+!\(", 1), (r"::", 1), (r"\bpub\s+(fn|struct|enum)\b", 3)])),
+        ("py", patterns(&[
+            (r"(?m)^\s*def\s+\w+\(.*\)\s*:", 3), (r"(?m)^\s*(from\s+\S+\s+)?import\s+\w+", 2),
+            (r"\bself\.", 1), (r"(?m)^\s*elif\b", 3), (r"\bprint\(", 1)])),
+        ("go", patterns(&[
+            (r"(?m)^package\s+\w+", 3), (r"\bfunc\s+", 2), (r":=", 1),
+            (r"\bfmt\.", 2)])),
+        ("js", patterns(&[
+            (r"\bfunction\s*\w*\s*\(", 2), (r"\b(const|let|var)\s+\w+\s*=", 1),
+            (r"=>", 1), (r"\bconsole\.log\(", 3), (r"\brequire\(", 2)])),
+        ("c", patterns(&[
+            (r"(?m)^#include\s*[<\x22]", 3), (r"\bint\s+main\s*\(", 3),
+            (r"\bprintf\(", 1)])),
+        ("java", patterns(&[
+            (r"\bpublic\s+(static\s+)?(class|void)\b", 3), (r"\bSystem\.out\.", 3)])),
+        ("sql", patterns(&[
+            (r"(?i)\bselect\b.*\bfrom\b", 3), (r"(?i)\binsert\s+into\b", 3),
+            (r"(?i)\bcreate\s+table\b", 3), (r"(?i)\bwhere\b", 1)])),
+        ("html", patterns(&[
+            (r"(?i)<!doctype\s+html", 4), (r"(?i)<(html|head|body|div|span|p)\b", 2)])),
+        ("yaml", patterns(&[
+            (r"(?m)^[A-Za-z_][\w-]*:\s*$", 1), (r"(?m)^[A-Za-z_][\w-]*:\s+\S", 1),
+            (r"(?m)^\s+[A-Za-z_][\w-]*:\s+\S", 2), (r"(?m)^\s*-\s+\w+", 1),
+            (r"(?m)^---\s*$", 2)])),
+        ("Dockerfile", patterns(&[
+            (r"(?m)^FROM\s+\S+", 3), (r"(?m)^(RUN|CMD|ENTRYPOINT|COPY|WORKDIR)\s", 2)])),
+        ("sh", patterns(&[
+            (r"(?m)^\s*\$\s+\w", 2), (r"\b(echo|export|sudo|grep|awk|sed|curl)\b", 1),
+            (r"\b(kubectl|docker|git|systemctl|ssh|tar|find)\s", 1),
+            (r"\s(\||&&|\|\|)\s", 1), (r"\$\{?\w+\}?", 1), (r"(?m)^\s*(if|for|while)\s.*;\s*(then|do)\b", 3)])),
+    ];
+}
+
+// Compile the patterns of the heuristic classifier
+fn patterns(patterns: &[(&str, usize)]) -> Vec<(regex::Regex, usize)> {
+    patterns.iter()
+        .map(|(pattern, weight)| (regex::Regex::new(pattern).unwrap(), *weight))
+        .collect()
+}
+
+// Detect the data extension of data. Only extensions known to the syntax set
+// are returned.
+pub fn extension(data: &str, ps: &SyntaxSet) -> Option<String> {
+    let known = |ext: String| {
+        if ps.find_syntax_by_extension(&ext).is_some() {
+            Some(ext)
+        } else {
+            None
+        }
+    };
+
+    modeline(data, ps).and_then(known)
+        .or_else(|| shebang(data).and_then(known))
+        .or_else(|| first_line(data, ps).and_then(known))
+        .or_else(|| heuristic(data).and_then(known))
+}
+
+// Find the language in a vim or emacs modeline at the start or end of data
+fn modeline(data: &str, ps: &SyntaxSet) -> Option<String> {
+    let lines: Vec<&str> = data.lines().collect();
+    let head = lines.iter().take(MODELINE_LINES);
+    let tail = lines.iter().rev().take(MODELINE_LINES);
+
+    for line in head.chain(tail) {
+        let name = if let Some(captures) = VIM.captures(line) {
+            captures.get(1)
+        } else if let Some(captures) = EMACS.captures(line) {
+            captures.get(1).or_else(|| captures.get(2))
+        } else {
+            None
+        };
+
+        if let Some(name) = name {
+            return language_extension(&name.as_str().to_lowercase(), ps);
+        }
+    }
+
+    None
+}
+
+// Map a language name, as used in modelines, to an extension
+fn language_extension(name: &str, ps: &SyntaxSet) -> Option<String> {
+    match name {
+        "sh" | "bash" | "zsh" | "shell-script" => Some("sh".to_string()),
+        "python" => Some("py".to_string()),
+        "ruby" => Some("rb".to_string()),
+        "perl" => Some("pl".to_string()),
+        "javascript" | "js" => Some("js".to_string()),
+        "markdown" => Some("md".to_string()),
+        "yaml" => Some("yaml".to_string()),
+        "rust" => Some("rs".to_string()),
+        _ => ps.find_syntax_by_token(name)
+            .and_then(|syntax| syntax.file_extensions.first())
+            .map(|ext| ext.to_string()),
+    }
+}
+
+// Map the interpreter of a shebang line to an extension
+fn shebang(data: &str) -> Option<String> {
+    let first = data.lines().next()?;
+    let interpreter = SHEBANG.captures(first)?.get(1)?.as_str();
+
+    let ext = match interpreter {
+        "sh" | "bash" | "zsh" | "dash" | "ksh" | "ash" => "sh",
+        "python" | "pypy" => "py",
+        "perl" => "pl",
+        "ruby" => "rb",
+        "node" | "nodejs" | "deno" => "js",
+        "php" => "php",
+        "lua" => "lua",
+        "awk" | "gawk" => "awk",
+        "tclsh" | "wish" => "tcl",
+        "Rscript" => "r",
+        _ => return None,
+    };
+
+    Some(ext.to_string())
+}
+
+// Use syntect's first line matching, for example "<?xml" or "<?php"
+fn first_line(data: &str, ps: &SyntaxSet) -> Option<String> {
+    let first = data.lines().find(|line| !line.trim().is_empty())?;
+    ps.find_syntax_by_first_line(first)
+        .and_then(|syntax| syntax.file_extensions.first())
+        .map(|ext| ext.to_string())
+}
+
+// Score data against the patterns of each language and return the extension
+// with the highest score, if it reaches the minimum score.
+fn heuristic(data: &str) -> Option<String> {
+    if serde_json::from_str::<serde_json::Value>(data).map(|v| v.is_object() || v.is_array())
+        .unwrap_or(false) {
+        return Some("json".to_string());
+    }
+
+    HEURISTICS.iter()
+        .map(|(ext, patterns)| {
+            let score: usize = patterns.iter()
+                .filter(|(regex, _)| regex.is_match(data))
+                .map(|(_, weight)| weight)
+                .sum();
+            (ext, score)
+        })
+        .filter(|(_, score)| *score >= MIN_SCORE)
+        .max_by_key(|(_, score)| *score)
+        .map(|(ext, _)| ext.to_string())
+}
@@ -0,0 +1,105 @@
+// This file contains functionality to compare revisions of a Tip (diff
+// subcommand), as a unified diff of the metadata and data laid out like the
+// update template.
+//
+//      tips diff <id>                  previous revision against the tip
+//      tips diff <id> <rev1>           rev1 against the tip
+//      tips diff <id> <rev1> <rev2>    rev1 against rev2
+
+use similar::{ChangeTag, TextDiff};
+
+
+// Escapes used when colors are enabled
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const CYAN: &str = "\x1b[36m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+// Number of unchanged lines shown around changes
+const CONTEXT: usize = 3;
+
+// Entry point for diff subcommand
+pub fn diff(matches: &clap::ArgMatches) {
+
+    // Since arg "id" is required it is safe to call unwrap here
+    let tips = crate::tips::Tips::load();
+    let tip = &tips.tips[tips.find(matches.value_of("id").unwrap())];
+    let id = tip.metadata.id.unwrap_or(0);
+    let revisions = crate::revision::load(tip);
+
+    // Get the text and label of a revision, or of the tip if None
+    let version = |revision: Option<&str>| -> (String, String) {
+        match revision {
+            Some(revision) => {
+                let number = crate::helpers::parse_id(revision);
+                match revisions.iter().find(|r| r.revision == number) {
+                    Some(r) => (r.text(), format!("{}@{}", id, number)),
+                    None    => {
+                        eprintln!("No revision {} of tip {}", number, id);
+                        std::process::exit(1);
+                    },
+                }
+            },
+            None => (crate::revision::Revision::of(tip, 0, "").text(), format!("{}", id)),
+        }
+    };
+
+    // Default to the revision before the latest one
+    let previous = revisions.len().saturating_sub(1).max(1).to_string();
+    let (old, old_label) = match matches.value_of("rev1") {
+        Some(rev1) => version(Some(rev1)),
+        None if revisions.is_empty() => {
+            println!("No revisions of tip {}", id);
+            return;
+        },
+        None => version(Some(&previous)),
+    };
+    let (new, new_label) = version(matches.value_of("rev2"));
+
+    crate::pager::page(&unified(&old, &new, &old_label, &new_label), true);
+}
+
+// Return the unified diff of old and new, colored if colors are enabled
+fn unified(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    let color = crate::color::enabled();
+    let paint = |escape: &str, text: &str| -> String {
+        if color {
+            format!("{}{}{}", escape, text, RESET)
+        } else {
+            text.to_string()
+        }
+    };
+
+    let diff = TextDiff::from_lines(old, new);
+    let mut output = String::new();
+    output.push_str(&paint(BOLD, &format!("--- {}", old_label)));
+    output.push('\n');
+    output.push_str(&paint(BOLD, &format!("+++ {}", new_label)));
+    output.push('\n');
+
+    for hunk in diff.unified_diff().context_radius(CONTEXT).iter_hunks() {
+        output.push_str(&paint(CYAN, &hunk.header().to_string()));
+        output.push('\n');
+
+        for change in hunk.iter_changes() {
+            let (sign, escape) = match change.tag() {
+                ChangeTag::Delete => ("-", RED),
+                ChangeTag::Insert => ("+", GREEN),
+                ChangeTag::Equal  => (" ", ""),
+            };
+            let line = format!("{}{}", sign, change.value().trim_end_matches('\n'));
+            if escape.is_empty() {
+                output.push_str(&line);
+            } else {
+                output.push_str(&paint(escape, &line));
+            }
+            output.push('\n');
+            if change.missing_newline() {
+                output.push_str("\\ No newline at end of file\n");
+            }
+        }
+    }
+
+    output
+}
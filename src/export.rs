@@ -0,0 +1,309 @@
+// This file contains functionality to export the tips (export subcommand).
+//
+// The markdown format writes one .md file per Tip to a directory, suitable
+// for a docs repository:
+//
+//      ---
+//      id: 12
+//      subject: Prune docker
+//      tags:
+//        - docker
+//      created: "2019-05-01T10:00:00+02:00"
+//      last_updated: ~
+//      ---
+//
+//      # Prune docker
+//
+//      ```sh
+//      docker system prune
+//      ```
+//
+// Tips with Markdown data are written as is instead of in a fenced block.
+// Parts follow as sections named by the part. The file names are the
+// slugified subjects, where a subject already used by a Tip with a lower id
+// gets the short uuid appended, so repeated exports give the same names. An
+// index.md lists the tips by tag. The files written are listed in
+// .tips-export, so files of tips no longer exported are removed next time.
+//
+// The json format writes all tips, including their contents, as one
+// document used as backup, see backup.rs.
+//
+// The html format writes a static site to a directory, with an index, a page
+// per tag and a page per Tip, highlighted using the configured theme, and a
+// search running in the browser, see site.rs. The attachments are copied, so
+// the site can be browsed without the tips database. As for Markdown, files
+// of a previous export no longer written are removed.
+
+use chrono::DateTime;
+use chrono::offset::Local;
+use serde::{Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::{fs, path};
+
+
+// File in the export directory listing the exported files
+const MANIFEST: &str = ".tips-export";
+
+// Longest slug used in a file name
+const MAX_SLUG: usize = 60;
+
+// Struct holding the front matter of an exported Tip
+#[derive(Serialize)]
+struct FrontMatter {
+    id: usize,
+    subject: String,
+    tags: Vec<String>,
+    created: Option<DateTime<Local>>,
+    last_updated: Option<DateTime<Local>>,
+}
+
+// Entry point for export subcommand
+pub fn export(matches: &clap::ArgMatches) {
+    match matches.subcommand() {
+        ("markdown", Some(matches)) => markdown(matches),
+        ("json", Some(matches))     => json(matches),
+        ("html", Some(matches))     => html(matches),
+        _                           => {
+            println!("{}", matches.usage());
+            std::process::exit(1);
+        },
+    }
+}
+
+// Export the tips as Markdown files to the directory given
+fn markdown(matches: &clap::ArgMatches) {
+
+    // Since arg "dir" is required it is safe to call unwrap here
+    let dir = path::Path::new(matches.value_of("dir").unwrap());
+    crate::helpers::create_dir_all(&dir.to_string_lossy());
+
+    let mut tips = crate::tips::Tips::load();
+    tips.tips.sort_by_key(|tip| tip.metadata.id);
+    let names = file_names(&tips.tips, "md");
+
+    let mut written = BTreeSet::new();
+    for (tip, name) in tips.tips.iter().zip(names.iter()) {
+        crate::helpers::write_to_file(&dir.join(name).to_string_lossy(), &markdown_tip(tip));
+        written.insert(name.to_string());
+    }
+
+    crate::helpers::write_to_file(&dir.join("index.md").to_string_lossy(), &index(&tips.tips, &names));
+    written.insert("index.md".to_string());
+    remove_stale(dir, &written);
+
+    println!("Exported {} tip(s) to {}", tips.tips.len(), dir.display());
+}
+
+// Export the tips as a backup document to the output file, or to stdout
+fn json(matches: &clap::ArgMatches) {
+    let tips = crate::tips::Tips::load();
+    let backup = crate::backup::Backup::of(&tips);
+    let json = match serde_json::to_string_pretty(&backup) {
+        Ok(json) => json,
+        Err(error) => panic!("Error serialize tips to json\n{}", error),
+    };
+
+    match matches.value_of("output") {
+        Some(output) => {
+            crate::helpers::write_to_file(output, &format!("{}\n", json));
+            println!("Exported {} tip(s) to {}", backup.tips.len(), output);
+        },
+        None => println!("{}", json),
+    }
+}
+
+// Export the tips as a static HTML site to the directory given
+fn html(matches: &clap::ArgMatches) {
+
+    // Since arg "dir" is required it is safe to call unwrap here
+    let dir = path::Path::new(matches.value_of("dir").unwrap());
+    crate::helpers::create_dir_all(&dir.join("tips").to_string_lossy());
+    crate::helpers::create_dir_all(&dir.join("tags").to_string_lossy());
+
+    let mut tips = crate::tips::Tips::load();
+    tips.tips.sort_by_key(|tip| tip.metadata.id);
+    let names = file_names(&tips.tips, "html");
+
+    // The tag pages are named as the tip pages, by slug
+    let mut used = BTreeSet::new();
+    let tag_pages: BTreeMap<String, String> = tips.tips.iter()
+        .flat_map(|tip| tip.metadata.tags.clone().unwrap_or_default())
+        .collect::<BTreeSet<String>>()
+        .into_iter()
+        .map(|tag| {
+            let slug = slugify(&tag);
+            let mut page = format!("{}.html", slug);
+            let mut n = 1;
+            while !used.insert(page.to_string()) {
+                n += 1;
+                page = format!("{}-{}.html", slug, n);
+            }
+            (tag, page)
+        })
+        .collect();
+
+    let site = crate::site::Site::new(&tips.tips, names.clone(), tag_pages.clone());
+    let mut written = BTreeSet::new();
+    let mut write = |name: String, contents: &str| {
+        crate::helpers::write_to_file(&dir.join(&name).to_string_lossy(), contents);
+        written.insert(name);
+    };
+
+    write("index.html".to_string(), &site.index());
+    write("style.css".to_string(), crate::site::STYLE);
+    write("search.js".to_string(), crate::site::SEARCH);
+    write("search-index.js".to_string(), &site.search_index());
+    for (tag, page) in &tag_pages {
+        write(format!("tags/{}", page), &site.tag_page(tag));
+    }
+    for (position, name) in names.iter().enumerate() {
+        write(format!("tips/{}", name), &site.tip_page(position));
+    }
+
+    for attachment in tips.tips.iter().flat_map(|tip| tip.attachments.iter()) {
+        let name = crate::site::attachment_path(attachment);
+        let file = dir.join(&name);
+        if let Some(parent) = file.parent() {
+            crate::helpers::create_dir_all(&parent.to_string_lossy());
+        }
+        if let Err(error) = fs::copy(attachment.path(), &file) {
+            panic!("Error copying attachment {} to {}\n{}", attachment.path(), file.display(), error)
+        }
+        written.insert(name);
+    }
+
+    remove_stale(dir, &written);
+    println!("Exported {} tip(s) to {}", tips.tips.len(), dir.display());
+}
+
+// Remove the files of the previous export to dir not written this time, and
+// list the files written for the next export
+fn remove_stale(dir: &path::Path, written: &BTreeSet<String>) {
+    let manifest = dir.join(MANIFEST);
+    if manifest.exists() {
+        for name in crate::helpers::read_to_string(&manifest.to_string_lossy()).lines() {
+            let file = dir.join(name);
+            if !written.contains(name) && file.is_file() {
+                crate::helpers::remove_file(&file.to_string_lossy());
+            }
+        }
+    }
+    let listing: String = written.iter().map(|name| format!("{}\n", name)).collect();
+    crate::helpers::write_to_file(&manifest.to_string_lossy(), &listing);
+}
+
+// Return the Markdown file contents of tip
+fn markdown_tip(tip: &crate::tip::Tip) -> String {
+    let front = FrontMatter {
+        id: tip.metadata.id.unwrap_or(0),
+        subject: tip.metadata.subject.to_string(),
+        tags: tip.metadata.tags.clone().unwrap_or_default(),
+        created: tip.metadata.created,
+        last_updated: tip.metadata.last_updated,
+    };
+    let front = match serde_yaml::to_string(&front) {
+        Ok(front) => front,
+        Err(error) => {
+            panic!("Error serialize front matter of tip {}\n{}", front.id, error)
+        },
+    };
+
+    let mut contents = format!("{}---\n\n# {}\n\n", front, tip.metadata.subject);
+    contents.push_str(&block(&tip.get_data(), &tip.metadata.data_extension));
+
+    for part in &tip.parts {
+        contents.push_str(&format!("\n## {}\n\n", part.name));
+        contents.push_str(&block(&part.get_data(), &part.extension));
+    }
+
+    if !tip.attachments.is_empty() {
+        contents.push_str("\n## Attachments\n\n");
+        for attachment in &tip.attachments {
+            contents.push_str(&format!("- {} ({}, {})\n",
+                                       attachment.name,
+                                       crate::columns::human_size(attachment.size),
+                                       attachment.mime));
+        }
+    }
+
+    contents
+}
+
+// Return data as Markdown: as is if Markdown, otherwise in a fenced block
+// with extension as language. The fence is longer than any backtick run in
+// data.
+fn block(data: &str, extension: &Option<String>) -> String {
+    let data = if data.ends_with('\n') { data.to_string() } else { format!("{}\n", data) };
+    let extension = extension.clone().unwrap_or_default();
+    if extension == "md" || extension == "markdown" {
+        return data;
+    }
+
+    let longest = data.split(|c| c != '`').map(|run| run.len()).max().unwrap_or(0);
+    let fence = "`".repeat(std::cmp::max(3, longest + 1));
+    format!("{}{}\n{}{}\n", fence, extension, data, fence)
+}
+
+// Return the index page, linking the tips grouped by tag
+fn index(tips: &[crate::tip::Tip], names: &[String]) -> String {
+    let mut tags: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    let mut untagged = Vec::new();
+    for (position, tip) in tips.iter().enumerate() {
+        match &tip.metadata.tags {
+            Some(tip_tags) if !tip_tags.is_empty() => {
+                for tag in tip_tags {
+                    tags.entry(tag.to_string()).or_default().push(position);
+                }
+            },
+            _ => untagged.push(position),
+        }
+    }
+
+    let mut contents = String::from("# Tips\n");
+    let groups = tags.into_iter()
+        .chain(std::iter::once(("Untagged".to_string(), untagged)))
+        .filter(|(_, positions)| !positions.is_empty());
+    for (tag, positions) in groups {
+        contents.push_str(&format!("\n## {}\n\n", tag));
+        for position in positions {
+            contents.push_str(&format!("- [{}]({})\n", tips[position].metadata.subject, names[position]));
+        }
+    }
+
+    contents
+}
+
+// Return the file name, with extension, of each of tips, which must be
+// sorted by id
+fn file_names(tips: &[crate::tip::Tip], extension: &str) -> Vec<String> {
+
+    // The index page is written next to the tips
+    let mut used: BTreeSet<String> = std::iter::once("index".to_string()).collect();
+    tips.iter()
+        .map(|tip| {
+            let slug = slugify(&tip.metadata.subject);
+            if used.insert(slug.to_string()) {
+                format!("{}.{}", slug, extension)
+            } else {
+                format!("{}-{}.{}", slug, tip.short_uuid(), extension)
+            }
+        })
+        .collect()
+}
+
+// Return text in lower case with every run of other characters than letters
+// and digits replaced by a dash
+fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars().flat_map(|c| c.to_lowercase()) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    let slug: String = slug.chars().take(MAX_SLUG).collect();
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() { "tip".to_string() } else { slug.to_string() }
+}
@@ -0,0 +1,105 @@
+// This file contains functionality to interactively filter tips, used by
+// the tui and pick subcommands.
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+
+
+// Enum describing how the query is matched against tips
+#[derive(Clone, Copy, PartialEq)]
+pub enum Matching {
+
+    // Fuzzy match the query against subject and tags, and find it as a
+    // (case insensitive) substring of the data.
+    Fuzzy,
+
+    // Match the query as a regex against subject, tags and data.
+    Regex,
+}
+
+impl Matching {
+
+    // Functions
+
+    // Name of the matching, shown to the user
+    pub fn name(self) -> &'static str {
+        match self {
+            Matching::Fuzzy => "fuzzy",
+            Matching::Regex => "regex",
+        }
+    }
+
+    // The other matching
+    pub fn toggle(self) -> Matching {
+        match self {
+            Matching::Fuzzy => Matching::Regex,
+            Matching::Regex => Matching::Fuzzy,
+        }
+    }
+}
+
+// Struct holding the text of a Tip that is matched against
+pub struct Entry {
+
+    // Subject and tags of the Tip
+    pub header: String,
+
+    // Data of the Tip, empty if data is not matched against
+    pub data: String,
+}
+
+impl Entry {
+
+    // Associated functions
+
+    // Create an Entry for tip, reading its data if with_data
+    pub fn new(tip: &crate::tip::Tip, with_data: bool) -> Entry {
+        let tags = tip.metadata.tags.clone().unwrap_or_default();
+        Entry {
+            header: format!("{} {}", tip.metadata.subject, tags.join(" ")),
+            data: if with_data { tip.get_all_data() } else { String::new() },
+        }
+    }
+}
+
+// Return the indexes of the entries matching query. Fuzzy matches are
+// ordered best first, with matches in the header before matches in data
+// only. An empty query matches all entries. Returns an error message if the
+// query is not a valid regex.
+pub fn filter(entries: &[Entry], query: &str, matching: Matching) -> Result<Vec<usize>, String> {
+    if query.is_empty() {
+        return Ok((0..entries.len()).collect());
+    }
+
+    match matching {
+        Matching::Fuzzy => {
+            let matcher = SkimMatcherV2::default().ignore_case();
+            let lower = query.to_lowercase();
+
+            let mut scored: Vec<(i64, usize)> = entries.iter().enumerate()
+                .filter_map(|(index, entry)| {
+                    match matcher.fuzzy_match(&entry.header, query) {
+                        Some(score) => Some((score, index)),
+                        None if entry.data.to_lowercase().contains(&lower) => Some((0, index)),
+                        None => None,
+                    }
+                })
+                .collect();
+
+            // Sort by best score, keeping the order of tips for equal scores
+            scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+            Ok(scored.into_iter().map(|(_, index)| index).collect())
+        },
+        Matching::Regex => {
+            let regex = match regex::Regex::new(query) {
+                Ok(regex) => regex,
+                Err(_)    => return Err(format!("Invalid regex: {}", query)),
+            };
+
+            Ok(entries.iter().enumerate()
+               .filter(|(_, entry)| regex.is_match(&entry.header) || regex.is_match(&entry.data))
+               .map(|(index, _)| index)
+               .collect())
+        },
+    }
+}
@@ -0,0 +1,266 @@
+// This file contains functionality to present tips in machine readable
+// formats, so scripts do not need to scrape the prettytable output.
+//
+// The json and yaml formats share the same document layout:
+//
+//      {
+//        "version": 1,
+//        "tips": [
+//          {
+//            "id": 1,
+//            "subject": "My first tip",
+//            "tags": ["tip"],
+//            "extension": "txt",
+//            "created": "2019-05-01T10:00:00+02:00",
+//            "last_updated": null,
+//            "data": "0b0e2c52-...",
+//            "path": "/home/user/.tips/data/0b0e2c52-...",
+//            "content": "...",
+//            "parts": [
+//              {
+//                "name": "manifest",
+//                "extension": "yaml",
+//                "data": "5f1d7a3e-...",
+//                "path": "/home/user/.tips/data/5f1d7a3e-...",
+//                "content": "..."
+//              }
+//            ],
+//            "attachments": [
+//              {
+//                "name": "screenshot.png",
+//                "size": 52311,
+//                "mime": "image/png",
+//                "sha256": "9f86d081...",
+//                "path": "/home/user/.tips/data/attachments/9f86d081..."
+//              }
+//            ]
+//          }
+//        ]
+//      }
+//
+// The "content" fields are only present when content was requested. Fields
+// are never removed from the document, new fields may be added, in which
+// case the version is left unchanged.
+
+use crate::statics::{CONFIG};
+use serde::{Serialize};
+use chrono::DateTime;
+use chrono::offset::Local;
+use std::io;
+
+
+// Version of the json/yaml document layout described above
+const SCHEMA_VERSION: usize = 1;
+
+// Values accepted by the --format argument
+pub static FORMATS: &[&str] = &["table", "json", "yaml", "csv", "tsv", "ids"];
+
+// Enum describing the output format
+pub enum Format {
+
+    // Human readable output using prettytable (and syntect for show)
+    Table,
+
+    // JSON document, see layout above
+    Json,
+
+    // YAML document, same layout as JSON
+    Yaml,
+
+    // Comma separated values, one tip per line with a header line
+    Csv,
+
+    // Tab separated values, one tip per line with a header line
+    Tsv,
+
+    // Only the tip id, one per line
+    Ids,
+}
+
+impl Format {
+
+    // Associated functions
+
+    // Get the Format from the --format argument. Defaults to Table when the
+    // argument is not present.
+    pub fn from_matches(matches: &clap::ArgMatches) -> Format {
+        match matches.value_of("format") {
+            Some("json") => Format::Json,
+            Some("yaml") => Format::Yaml,
+            Some("csv")  => Format::Csv,
+            Some("tsv")  => Format::Tsv,
+            Some("ids")  => Format::Ids,
+            _            => Format::Table,
+        }
+    }
+}
+
+// Struct holding one tip as presented in the machine readable formats
+#[derive(Serialize)]
+struct Record {
+    id: usize,
+    subject: String,
+    tags: Vec<String>,
+    extension: Option<String>,
+    created: Option<DateTime<Local>>,
+    last_updated: Option<DateTime<Local>>,
+    data: uuid::Uuid,
+    path: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+
+    parts: Vec<PartRecord>,
+    attachments: Vec<AttachmentRecord>,
+}
+
+// Struct holding one part of a tip as presented in the machine readable
+// formats
+#[derive(Serialize)]
+struct PartRecord {
+    name: String,
+    extension: Option<String>,
+    data: uuid::Uuid,
+    path: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+}
+
+// Struct holding one attachment of a tip as presented in the machine
+// readable formats
+#[derive(Serialize)]
+struct AttachmentRecord {
+    name: String,
+    size: u64,
+    mime: String,
+    sha256: String,
+    path: String,
+}
+
+impl Record {
+
+    // Associated functions
+
+    // Create a Record from a Tip, reading the data contents only if asked to
+    fn new(tip: &crate::tip::Tip, with_content: bool) -> Record {
+        Record {
+            id: tip.metadata.id.unwrap_or(0),
+            subject: tip.metadata.subject.to_string(),
+            tags: tip.metadata.tags.clone().unwrap_or_default(),
+            extension: tip.metadata.data_extension.clone(),
+            created: tip.metadata.created,
+            last_updated: tip.metadata.last_updated,
+            data: tip.data,
+            path: format!("{}/{}", &CONFIG.data, tip.data),
+            content: if with_content { Some(tip.get_data()) } else { None },
+            parts: tip.parts.iter()
+                .map(|part| PartRecord {
+                    name: part.name.to_string(),
+                    extension: part.extension.clone(),
+                    data: part.data,
+                    path: part.path(),
+                    content: if with_content { Some(part.get_data()) } else { None },
+                })
+                .collect(),
+            attachments: tip.attachments.iter()
+                .map(|attachment| AttachmentRecord {
+                    name: attachment.name.to_string(),
+                    size: attachment.size,
+                    mime: attachment.mime.to_string(),
+                    sha256: attachment.sha256.to_string(),
+                    path: attachment.path(),
+                })
+                .collect(),
+        }
+    }
+}
+
+// Struct holding the complete document for json and yaml
+#[derive(Serialize)]
+struct Document {
+    version: usize,
+    tips: Vec<Record>,
+}
+
+// Write the tips to stdout in the given (machine readable) format. The Table
+// format is not handled here, since it differs between list and show.
+pub fn output(tips: &[&crate::tip::Tip], format: &Format, with_content: bool) {
+    let records: Vec<Record> = tips.iter()
+        .map(|tip| Record::new(tip, with_content))
+        .collect();
+
+    match format {
+        Format::Json => {
+            let document = Document { version: SCHEMA_VERSION, tips: records };
+            match serde_json::to_string_pretty(&document) {
+                Ok(json) => println!("{}", json),
+                Err(error) => panic!("Error serialize tips to json\n{}", error),
+            }
+        },
+        Format::Yaml => {
+            let document = Document { version: SCHEMA_VERSION, tips: records };
+            match serde_yaml::to_string(&document) {
+                Ok(yaml) => println!("{}", yaml),
+                Err(error) => panic!("Error serialize tips to yaml\n{}", error),
+            }
+        },
+        Format::Csv => delimited(&records, b',', with_content),
+        Format::Tsv => delimited(&records, b'\t', with_content),
+        Format::Ids => {
+            for record in records.iter() {
+                println!("{}", record.id);
+            }
+        },
+        Format::Table => panic!("Table format is not a machine readable format"),
+    }
+}
+
+// Write the records as delimiter separated values, starting with a header
+// line. Tags are separated by a space within the tags column.
+fn delimited(records: &[Record], delimiter: u8, with_content: bool) {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(io::stdout());
+
+    let mut header = vec![
+        "id", "subject", "tags", "extension", "created", "last_updated",
+        "data", "path"];
+    if with_content {
+        header.push("content");
+    }
+    write_record(&mut writer, &header);
+
+    for record in records.iter() {
+        let mut fields = vec![
+            record.id.to_string(),
+            record.subject.to_string(),
+            record.tags.join(" "),
+            record.extension.clone().unwrap_or_default(),
+            record.created.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+            record.last_updated.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+            record.data.to_string(),
+            record.path.to_string(),
+        ];
+        if let Some(content) = &record.content {
+            fields.push(content.to_string());
+        }
+        write_record(&mut writer, &fields);
+    }
+
+    if let Err(error) = writer.flush() {
+        panic!("Error flushing delimited output\n{}", error);
+    }
+}
+
+// Write a single record using the csv writer
+fn write_record<W, I, T>(writer: &mut csv::Writer<W>, record: I)
+where
+    W: io::Write,
+    I: IntoIterator<Item = T>,
+    T: AsRef<[u8]>,
+{
+    if let Err(error) = writer.write_record(record) {
+        panic!("Error writing delimited output\n{}", error);
+    }
+}
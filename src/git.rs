@@ -0,0 +1,123 @@
+// This file contains functionality for git mode, where the tips home is a git
+// repository and every change to the tips is committed.
+//
+// Git mode is enabled by the git config, set by "tips init --git". The
+// commit messages describe the change and the Tip, as in
+// "update tip 42: subject", so "tips history" can list the tip changes.
+//
+// The git subcommand runs git in the tips home, passing all arguments.
+
+use crate::statics::{CONFIG};
+use std::{path, process};
+
+
+// Files in the tips home that are not committed
+const IGNORED: &str = "tmp_file.yaml\nrun_history.yaml\nsync_base.yaml\n*.packdump\n*.themedump\n";
+
+// Pattern matching the commit messages of tip changes
+const TIP_CHANGE: &str = "^[a-z]+ tip [0-9]+:";
+
+// Make home a git repository, unless already one, and commit all files
+pub fn init_repository(home: &path::Path) {
+    if !home.join(".git").exists() {
+        run(home, &["init", "--quiet"]);
+    }
+
+    let gitignore = home.join(".gitignore");
+    if !gitignore.exists() {
+        crate::helpers::write_to_file(&gitignore.to_string_lossy(), IGNORED);
+    }
+
+    commit_in(home, "initialize tips");
+}
+
+// Commit all changes in the tips home with message, if git mode is enabled.
+// A failing commit does not undo the change, so it only gives a warning.
+pub fn commit(message: &str) {
+    if CONFIG.git {
+        commit_in(&CONFIG.home(), message);
+    }
+}
+
+// Return the commit message of a change of tip described by verb, as in
+// "update tip 42: subject"
+pub fn message(verb: &str, tip: &crate::tip::Tip) -> String {
+    format!("{} tip {}: {}", verb, tip.metadata.id.unwrap_or(0), tip.metadata.subject)
+}
+
+// Entry point for git subcommand
+pub fn git(matches: &clap::ArgMatches) {
+    enabled();
+
+    let args: Vec<&str> = matches.values_of("args").map(|args| args.collect()).unwrap_or_default();
+    process::exit(status(&CONFIG.home(), &args));
+}
+
+// Entry point for history subcommand, listing the commits of tip changes,
+// optionally only those of the tip with id.
+pub fn history(matches: &clap::ArgMatches) {
+    enabled();
+
+    // A uuid prefix can only refer to a Tip not removed
+    let pattern = match matches.value_of("id") {
+        Some(reference) => {
+            let id = reference.parse::<usize>().unwrap_or_else(|_| {
+                let tips = crate::tips::Tips::load();
+                tips.tips[tips.find(reference)].metadata.id.unwrap_or(0)
+            });
+            format!("^[a-z]+ tip {}:", id)
+        },
+        None => TIP_CHANGE.to_string(),
+    };
+    let grep = format!("--grep={}", pattern);
+
+    process::exit(status(&CONFIG.home(), &[
+        "log",
+        "--extended-regexp",
+        &grep,
+        "--date=format:%Y-%m-%d %H:%M:%S",
+        "--format=%h  %ad  %s",
+    ]));
+}
+
+// Exit with a message unless git mode is enabled
+fn enabled() {
+    if !CONFIG.git {
+        eprintln!("Git mode is not enabled, enable it with: tips init --git");
+        process::exit(1);
+    }
+}
+
+// Stage all changes in home and commit them, unless there are none
+fn commit_in(home: &path::Path, message: &str) {
+    if status(home, &["add", "--all"]) != 0 {
+        eprintln!("Warning: unable to stage changes in {}", home.display());
+        return;
+    }
+
+    // Nothing to commit if nothing is staged
+    if status(home, &["diff", "--cached", "--quiet"]) == 0 {
+        return;
+    }
+
+    if status(home, &["commit", "--quiet", "--message", message]) != 0 {
+        eprintln!("Warning: unable to commit changes in {}", home.display());
+    }
+}
+
+// Run git with args in home. Will panic if git can not be run or fails.
+pub fn run(home: &path::Path, args: &[&str]) {
+    if status(home, args) != 0 {
+        panic!("Error running git {} in {}", args.join(" "), home.display());
+    }
+}
+
+// Run git with args in home and return its exit code
+pub fn status(home: &path::Path, args: &[&str]) -> i32 {
+    match process::Command::new("git").arg("-C").arg(home).args(args).status() {
+        Ok(status) => status.code().unwrap_or(1),
+        Err(error) => {
+            panic!("Error running git {}\n{}", args.join(" "), error)
+        },
+    }
+}
@@ -68,3 +68,16 @@ pub fn read_to_string(path: &str) -> String {
         },
     }
 }
+
+// Function to convert a tip id argument to usize
+//
+// Will panic if error
+pub fn parse_id(id_str: &str) -> usize {
+    match id_str.parse::<usize>() {
+        Ok(id) => id,
+        Err(error) => {
+            panic!("Error converting str id {} to usize\n{}",
+                   id_str, error)
+        },
+    }
+}
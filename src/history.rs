@@ -0,0 +1,236 @@
+// This file contains functionality to read commands from the shell history,
+// used by add --from-history.
+//
+// The history file is $HISTFILE if set, otherwise the history file of the
+// shell in $SHELL. The following formats are supported:
+//      bash, one command per line. Timestamp lines (#1612345678) are skipped.
+//      zsh, one command per line, optionally in the extended format
+//      (: 1612345678:0;command). Multi line commands end with a backslash.
+//      fish, entries of the form "- cmd: command" followed by "when:" and
+//      "paths:" lines.
+
+use crate::filter::{Entry};
+use std::path::{PathBuf};
+use std::{env, fs, process};
+
+
+// Enum describing the format of a history file
+#[derive(Clone, Copy)]
+enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+// Let the user pick among the last n commands of the shell history, most
+// recent first. Returns the picked commands in the order they were run.
+// Exits if no command was picked.
+pub fn pick(n: usize) -> Vec<String> {
+    let (path, shell) = history_file();
+
+    let bytes = match fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(error) => {
+            panic!("Error reading history file {}\n{}",
+                   path.display(), error)
+        },
+    };
+
+    let commands = last(parse(&bytes, shell), n);
+    if commands.is_empty() {
+        eprintln!("No commands in history file {}", path.display());
+        process::exit(1);
+    }
+
+    // Show multi line commands on one line
+    let lines: Vec<String> = commands.iter().map(|c| c.replace('\n', " ↵ ")).collect();
+    let entries: Vec<Entry> = commands.iter()
+        .map(|c| Entry { header: c.to_string(), data: String::new() })
+        .collect();
+
+    let mut picked = crate::pick::Picker::new(lines, entries, "", true).run();
+    if picked.is_empty() {
+        eprintln!("Aborted by user");
+        process::exit(1);
+    }
+
+    // The commands are listed most recent first
+    picked.sort_unstable_by(|a, b| b.cmp(a));
+    picked.into_iter().map(|index| commands[index].to_string()).collect()
+}
+
+// Return the last n distinct commands, most recent first
+fn last(commands: Vec<String>, n: usize) -> Vec<String> {
+    let mut found: Vec<String> = Vec::new();
+
+    for command in commands.into_iter().rev() {
+        if found.len() == n {
+            break;
+        }
+        if !command.trim().is_empty() && !found.contains(&command) {
+            found.push(command);
+        }
+    }
+
+    found
+}
+
+// Return the history file and its format
+fn history_file() -> (PathBuf, Shell) {
+    let home = match env::var("HOME") {
+        Ok(home) => PathBuf::from(home),
+        Err(error) => {
+            panic!("Error reading environment variable HOME\n{}", error)
+        },
+    };
+
+    let shell = match env::var("SHELL") {
+        Ok(shell) if shell.ends_with("zsh")  => Shell::Zsh,
+        Ok(shell) if shell.ends_with("fish") => Shell::Fish,
+        _                                    => Shell::Bash,
+    };
+
+    // $HISTFILE is only exported by some setups, its format is guessed from
+    // the name of the file.
+    if let Ok(histfile) = env::var("HISTFILE") {
+        let path = PathBuf::from(histfile);
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let shell = if name.contains("zsh") {
+            Shell::Zsh
+        } else if name.contains("fish") {
+            Shell::Fish
+        } else {
+            shell
+        };
+        return (path, shell);
+    }
+
+    let path = match shell {
+        Shell::Bash => home.join(".bash_history"),
+        Shell::Zsh  => {
+            let zdotdir = env::var("ZDOTDIR").map(PathBuf::from).unwrap_or_else(|_| home.clone());
+            zdotdir.join(".zsh_history")
+        },
+        Shell::Fish => {
+            let data = env::var("XDG_DATA_HOME").map(PathBuf::from)
+                .unwrap_or_else(|_| home.join(".local/share"));
+            data.join("fish/fish_history")
+        },
+    };
+
+    (path, shell)
+}
+
+// Parse the contents of a history file into commands, oldest first
+fn parse(bytes: &[u8], shell: Shell) -> Vec<String> {
+    match shell {
+        Shell::Bash => parse_bash(&String::from_utf8_lossy(bytes)),
+        Shell::Zsh  => parse_zsh(&String::from_utf8_lossy(&unmetafy(bytes))),
+        Shell::Fish => parse_fish(&String::from_utf8_lossy(bytes)),
+    }
+}
+
+// Parse bash history, skipping timestamp lines
+fn parse_bash(data: &str) -> Vec<String> {
+    data.lines()
+        .filter(|line| !is_timestamp(line))
+        .map(|line| line.to_string())
+        .collect()
+}
+
+// Return true if line is a bash history timestamp
+fn is_timestamp(line: &str) -> bool {
+    line.len() > 1 && line.starts_with('#') && line[1..].chars().all(|c| c.is_ascii_digit())
+}
+
+// Parse zsh history, in plain or extended format
+fn parse_zsh(data: &str) -> Vec<String> {
+    let mut commands: Vec<String> = Vec::new();
+    let mut current: Option<String> = None;
+
+    for line in data.lines() {
+        let line = match current.take() {
+            Some(mut command) => {
+                command.push('\n');
+                command.push_str(line);
+                command
+            },
+            None => strip_extended(line).to_string(),
+        };
+
+        // A trailing backslash continues the command on the next line
+        match line.strip_suffix('\\') {
+            Some(command) => current = Some(command.to_string()),
+            None          => commands.push(line),
+        }
+    }
+
+    if let Some(command) = current {
+        commands.push(command);
+    }
+
+    commands
+}
+
+// Strip the ": <start>:<elapsed>;" prefix of the zsh extended format
+fn strip_extended(line: &str) -> &str {
+    if !line.starts_with(": ") {
+        return line;
+    }
+
+    match line.find(';') {
+        Some(index) if line[2..index].chars().all(|c| c.is_ascii_digit() || c == ':') => &line[index + 1..],
+        _ => line,
+    }
+}
+
+// zsh stores some bytes "metafied", as 0x83 followed by the byte xor 0x20
+fn unmetafy(bytes: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut iter = bytes.iter();
+
+    while let Some(byte) = iter.next() {
+        if *byte == 0x83 {
+            if let Some(next) = iter.next() {
+                result.push(next ^ 0x20);
+            }
+        } else {
+            result.push(*byte);
+        }
+    }
+
+    result
+}
+
+// Parse fish history, unescaping newlines and backslashes in commands
+fn parse_fish(data: &str) -> Vec<String> {
+    data.lines()
+        .filter_map(|line| line.strip_prefix("- cmd: "))
+        .map(unescape_fish)
+        .collect()
+}
+
+// Unescape a fish history command
+fn unescape_fish(command: &str) -> String {
+    let mut result = String::new();
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n')   => result.push('\n'),
+            Some('\\')  => result.push('\\'),
+            Some(other) => {
+                result.push('\\');
+                result.push(other);
+            },
+            None => result.push('\\'),
+        }
+    }
+
+    result
+}
+
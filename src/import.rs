@@ -0,0 +1,784 @@
+// This file contains functionality to import tips (import subcommand).
+//
+// The markdown format reads a directory tree of Markdown notes, as kept by
+// Obsidian or Jekyll or written by "tips export markdown". Each .md file
+// becomes a Tip:
+//
+//      subject       front matter subject or title, else the first heading,
+//                    else the file name
+//      tags          front matter tags, else the names of the folders
+//      created       front matter created or date
+//      last_updated  front matter last_updated, updated or modified
+//
+// A note that is a single fenced code block, optionally followed by "## name"
+// sections holding a fenced block each (as exported), is imported as that
+// code with the block language as data extension and the sections as parts.
+// Any other note is imported as Markdown. A note with the same contents as a
+// Tip, or as a note imported before it, is reported and skipped.
+//
+// The json format restores a backup written by "tips export json", see
+// backup.rs. Into an empty database all tips are added. Otherwise --merge
+// adds the tips not in the database, keeping those already in it, and
+// --replace replaces the tips with those in the backup, moving the tips not
+// in the backup to the trash. A Tip is identified by its uuid, and keeps its
+// id unless taken.
+//
+// The tldr, navi and cheat formats read cheat sheets, a single file or a
+// directory tree of them, and add a Tip for every command with its
+// description as subject and data extension sh:
+//
+//      tldr   pages/*/*.md, "- description:" followed by a `command` line.
+//             Tagged with the page name. {{path/to/file}} placeholders
+//             become {{path_to_file}}, {{[-v|--verbose]}} becomes --verbose.
+//      navi   *.cheat, "# description" followed by command lines. Tagged
+//             with the tags of the "%" line, else the file name. <var>
+//             placeholders become {{var}}; the "$ var:" suggestions are not
+//             imported.
+//      cheat  files in cheat/cheatsheets, "# description" lines followed by
+//             command lines. Tagged with the file name and the front matter
+//             tags, the front matter syntax gives the data extension.
+//
+// As for Markdown, a command already held by a Tip, or imported before it, is
+// reported and skipped.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone};
+use chrono::offset::Local;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use regex::{Captures, Regex};
+use std::io::{Read};
+use std::{io, path};
+
+
+// Data extension of imported commands
+const COMMAND_EXTENSION: &str = "sh";
+
+lazy_static::lazy_static! {
+    static ref TLDR_PLACEHOLDER: Regex = Regex::new(r"\{\{(.*?)\}\}").unwrap();
+    static ref TLDR_OPTION: Regex = Regex::new(r"^\[-[^|\]]*\|(--[^|\]]+)\]$").unwrap();
+    static ref TLDR_MNEMONIC: Regex = Regex::new(r"\[(\w+)\]").unwrap();
+    static ref NAVI_PLACEHOLDER: Regex = Regex::new(r"<([A-Za-z_][A-Za-z0-9_-]*)>").unwrap();
+}
+
+// Struct holding a note read from a Markdown file
+struct Note {
+    subject: Option<String>,
+    tags: Option<Vec<String>>,
+    created: Option<DateTime<Local>>,
+    last_updated: Option<DateTime<Local>>,
+    body: String,
+}
+
+// Struct holding the data of a note as a Tip
+struct Content {
+    data: String,
+    extension: Option<String>,
+    sections: Vec<crate::part::Section>,
+}
+
+// Struct holding a command read from a cheat sheet
+struct Command {
+    subject: String,
+    tags: Vec<String>,
+    extension: String,
+    data: String,
+}
+
+// Entry point for import subcommand
+pub fn import(matches: &clap::ArgMatches) {
+    match matches.subcommand() {
+        ("markdown", Some(matches)) => markdown(matches),
+        ("json", Some(matches))     => json(matches),
+        ("tldr", Some(matches))     => sheets(matches, "tldr"),
+        ("navi", Some(matches))     => sheets(matches, "navi"),
+        ("cheat", Some(matches))    => sheets(matches, "cheat"),
+        _                           => {
+            println!("{}", matches.usage());
+            std::process::exit(1);
+        },
+    }
+}
+
+// Import the Markdown files in the directory tree given
+fn markdown(matches: &clap::ArgMatches) {
+
+    // Since arg "dir" is required it is safe to call unwrap here
+    let root = path::Path::new(matches.value_of("dir").unwrap());
+    if !root.is_dir() {
+        eprintln!("No such directory: {}", root.display());
+        std::process::exit(1);
+    }
+
+    // The index written by "tips export markdown" is not a note
+    let exported = root.join(".tips-export").exists();
+
+    let mut known = known();
+    let ps = crate::assets::syntax_set();
+    let (mut imported, mut duplicates) = (0, 0);
+    for entry in walkdir::WalkDir::new(root).sort_by(|a, b| a.file_name().cmp(b.file_name())) {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(error) => {
+                panic!("Error reading directory {}\n{}", root.display(), error)
+            },
+        };
+
+        let file = entry.path();
+        let relative = file.strip_prefix(root).unwrap_or(file);
+        let hidden = relative.components().any(|c| c.as_os_str().to_string_lossy().starts_with('.'));
+        let markdown = file.extension().map(|e| e == "md" || e == "markdown").unwrap_or(false);
+        if hidden || !markdown || !entry.file_type().is_file() ||
+            (exported && relative == path::Path::new("index.md")) {
+            continue;
+        }
+
+        let note = read_note(file);
+        let (subject, body) = subject(&note, file);
+        let content = content(&body, &ps);
+        let data = crate::part::join_sections(&content.data, &content.sections);
+
+        let key = hash(&data);
+        if let Some(original) = known.get(&key) {
+            println!("Skipped {}, same contents as {}", relative.display(), original);
+            duplicates += 1;
+            continue;
+        }
+
+        // Without tags the folders are used as tags
+        let tags = note.tags.clone().unwrap_or_else(|| {
+            relative.parent()
+                .map(|parent| parent.components()
+                     .map(|c| c.as_os_str().to_string_lossy().to_string())
+                     .collect())
+                .unwrap_or_default()
+        });
+
+        let metadata = crate::metadata::Metadata {
+            subject,
+            id: None,
+            tags: Some(tags),
+            created: note.created,
+            last_updated: note.last_updated,
+            data_extension: content.extension,
+        };
+        crate::add::imported(&metadata, &data);
+        known.insert(key, relative.display().to_string());
+        imported += 1;
+    }
+
+    crate::git::commit(&format!("import {} tip(s) from {}", imported, root.display()));
+    println!("Imported {} tip(s), skipped {} duplicate(s)", imported, duplicates);
+}
+
+// Restore the backup in the file given, or read from stdin
+fn json(matches: &clap::ArgMatches) {
+    let contents = match matches.value_of("file") {
+        Some(file) if file != "-" => crate::helpers::read_to_string(file),
+        _ => {
+            let mut contents = String::new();
+            if let Err(error) = io::stdin().read_to_string(&mut contents) {
+                panic!("Error when trying to read from stdin\n{}", error)
+            }
+            contents
+        },
+    };
+    // The version is checked first, a newer layout may not parse
+    let document: serde_json::Value = match serde_json::from_str(&contents) {
+        Ok(document) => document,
+        Err(error) => {
+            eprintln!("Invalid backup document\n{}", error);
+            std::process::exit(1);
+        },
+    };
+    let version = document.get("version").and_then(|version| version.as_u64()).unwrap_or(0);
+    if version > crate::backup::BACKUP_VERSION as u64 {
+        eprintln!("Backup version {} is newer than the supported version {}, update tips to import it",
+                  version, crate::backup::BACKUP_VERSION);
+        std::process::exit(1);
+    }
+    let backup: crate::backup::Backup = match serde_json::from_value(document) {
+        Ok(backup) => backup,
+        Err(error) => {
+            eprintln!("Invalid backup document\n{}", error);
+            std::process::exit(1);
+        },
+    };
+
+    let replace = matches.is_present("replace");
+    let dry_run = matches.is_present("dry-run");
+    let mut tips = crate::tips::Tips::load();
+    if !tips.tips.is_empty() && !replace && !matches.is_present("merge") {
+        eprintln!("The database is not empty, import with --merge or --replace");
+        std::process::exit(1);
+    }
+
+    // With --replace every Tip is replaced, the ones not in the backup are
+    // moved to the trash
+    let uuids: BTreeSet<uuid::Uuid> = backup.tips.iter().map(|tip| tip.uuid).collect();
+    let mut next = tips.highest_id().max(backup.last_id) + 1;
+    let (mut replaced, mut trashed) = (Vec::new(), Vec::new());
+    if replace {
+        for tip in tips.tips.drain(..) {
+            if uuids.contains(&tip.data) {
+                replaced.push(tip);
+            } else {
+                trashed.push(tip);
+            }
+        }
+        tips.aliases = backup.aliases.clone();
+    }
+
+    let mut used: BTreeSet<usize> = tips.tips.iter().filter_map(|tip| tip.metadata.id).collect();
+    let (mut added, mut kept) = (0, 0);
+    let mut trash = crate::trash::Trash::load();
+    let mut untrashed = Vec::new();
+    for backup_tip in &backup.tips {
+        if tips.tips.iter().any(|tip| tip.data == backup_tip.uuid) {
+            kept += 1;
+            continue;
+        }
+
+        let id = if used.contains(&backup_tip.id) {
+            println!("Id {} is taken, tip \"{}\" gets id {}", backup_tip.id, backup_tip.subject, next);
+            next += 1;
+            next - 1
+        } else {
+            backup_tip.id
+        };
+        used.insert(id);
+        added += 1;
+        if dry_run {
+            continue;
+        }
+
+        // A Tip removed since the backup is restored under the same uuid as
+        // the copy in the trash, so the copy is taken out
+        let (same, others) = std::mem::take(&mut trash.tips).into_iter()
+            .partition(|trashed| trashed.tip.data == backup_tip.uuid);
+        trash.tips = others;
+        untrashed.extend(same);
+
+        let mut tip = backup_tip.restore();
+        tip.metadata.id = Some(id);
+        crate::revision::record(&tip, "imported");
+        tips.tips.push(tip);
+    }
+
+    let summary = format!("{} tip(s) added, {} kept, {} replaced and {} moved to trash",
+                          added - replaced.len(), kept, replaced.len(), trashed.len());
+    if dry_run {
+        println!("Dry run, would import: {}", summary);
+        return;
+    }
+
+    tips.tips.sort_by_key(|tip| tip.metadata.id);
+    tips.store();
+
+    // Remove the files no longer used by the replaced tips and the copies
+    // taken out of the trash, see remove::purge
+    if !untrashed.is_empty() {
+        trash.store();
+    }
+    for old in replaced.iter().chain(untrashed.iter().map(|trashed| &trashed.tip)) {
+        crate::remove::purge(old, &tips);
+    }
+    for tip in trashed {
+        crate::trash::put(tip);
+    }
+
+    crate::git::commit(&format!("import {} tip(s) from backup", added));
+    println!("Imported: {}", summary);
+}
+
+// Import the cheat sheets of format in the file or directory tree given
+fn sheets(matches: &clap::ArgMatches, format: &str) {
+
+    // Since arg "path" is required it is safe to call unwrap here
+    let root = path::Path::new(matches.value_of("path").unwrap());
+    if !root.exists() {
+        eprintln!("No such file or directory: {}", root.display());
+        std::process::exit(1);
+    }
+
+    let mut known = known();
+    let ps = crate::assets::syntax_set();
+    let (mut imported, mut duplicates) = (0, 0);
+    for entry in walkdir::WalkDir::new(root).sort_by(|a, b| a.file_name().cmp(b.file_name())) {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(error) => {
+                panic!("Error reading directory {}\n{}", root.display(), error)
+            },
+        };
+
+        let file = entry.path();
+        let relative = file.strip_prefix(root).unwrap_or(file);
+        let hidden = relative.components().any(|c| c.as_os_str().to_string_lossy().starts_with('.'));
+        let extension = file.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+        let wanted = match format {
+            "tldr" => extension == "md",
+            "navi" => extension == "cheat",
+            _      => extension != "md" && extension != "txt",
+        };
+        if hidden || !wanted || !entry.file_type().is_file() {
+            continue;
+        }
+
+        let name = file.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let contents = crate::helpers::read_to_string(&file.to_string_lossy()).replace("\r\n", "\n");
+        let commands = match format {
+            "tldr" => tldr_page(&name, &contents),
+            "navi" => navi_sheet(&name, &contents),
+            _      => cheat_sheet(&name, &contents, &ps),
+        };
+
+        for command in commands {
+            let key = hash(&command.data);
+            if let Some(original) = known.get(&key) {
+                println!("Skipped \"{}\" of {}, same command as {}", command.subject, name, original);
+                duplicates += 1;
+                continue;
+            }
+
+            let metadata = crate::metadata::Metadata {
+                subject: command.subject,
+                id: None,
+                tags: Some(command.tags),
+                created: None,
+                last_updated: None,
+                data_extension: Some(command.extension),
+            };
+            let id = crate::add::imported(&metadata, &command.data);
+            known.insert(key, format!("tip {}", id));
+            imported += 1;
+        }
+    }
+
+    crate::git::commit(&format!("import {} tip(s) from {} {}", imported, format, root.display()));
+    println!("Imported {} tip(s), skipped {} duplicate(s)", imported, duplicates);
+}
+
+// Return the commands of a tldr page: a "- description:" line followed by a
+// line holding the command in backticks. The brackets marking mnemonics, as
+// in "[c]reate", are removed from the description and it is capitalized.
+fn tldr_page(name: &str, contents: &str) -> Vec<Command> {
+    let mut page = name.to_string();
+    let mut description: Option<String> = None;
+    let mut commands = Vec::new();
+    for line in contents.lines().map(str::trim) {
+        if let Some(heading) = line.strip_prefix("# ") {
+            page = heading.trim().to_string();
+        } else if let Some(text) = line.strip_prefix("- ") {
+            let text = TLDR_MNEMONIC.replace_all(text.trim(), "$1");
+            let mut chars = text.chars();
+            description = chars.next().map(|first| first.to_uppercase().chain(chars).collect());
+        } else if line.len() > 1 && line.starts_with('`') && line.ends_with('`') {
+            let line = tldr_placeholders(&line[1..line.len() - 1]);
+            commands.push(command(description.take(), &[line], vec![page.to_string()], COMMAND_EXTENSION));
+        }
+    }
+
+    commands
+}
+
+// Return the commands of a navi cheat sheet: "# description" followed by
+// command lines. Lines starting with "$" (suggestions), ";" (comments) and
+// "@" (extends) end a command.
+fn navi_sheet(name: &str, contents: &str) -> Vec<Command> {
+    let mut tags = vec![name.to_string()];
+    let mut description: Option<String> = None;
+    let mut lines: Vec<String> = Vec::new();
+    let mut commands = Vec::new();
+    for line in contents.lines() {
+        let trimmed = line.trim();
+        let ends = trimmed.is_empty() || trimmed.starts_with(|c| "%#$;@".contains(c));
+        if ends && !lines.is_empty() {
+            commands.push(command(description.take(), &lines, tags.clone(), COMMAND_EXTENSION));
+            lines.clear();
+        }
+
+        if let Some(text) = trimmed.strip_prefix('%') {
+            tags = text.split(',').map(|tag| tag.trim().to_string()).filter(|tag| !tag.is_empty()).collect();
+            description = None;
+        } else if let Some(text) = trimmed.strip_prefix('#') {
+            description = Some(text.trim().to_string());
+        } else if !ends {
+            lines.push(NAVI_PLACEHOLDER.replace_all(line, "{{$1}}").to_string());
+        }
+    }
+    if !lines.is_empty() {
+        commands.push(command(description, &lines, tags, COMMAND_EXTENSION));
+    }
+
+    commands
+}
+
+// Return the commands of a cheat sheet: "# description" lines followed by
+// command lines, up to an empty line or the next description. Front matter
+// may give the syntax and tags.
+fn cheat_sheet(name: &str, contents: &str, ps: &syntect::parsing::SyntaxSet) -> Vec<Command> {
+    let mut tags = vec![name.to_string()];
+    let mut extension = COMMAND_EXTENSION.to_string();
+    let mut body = contents;
+    if let Some(end) = contents.strip_prefix("---\n").and_then(|rest| rest.find("\n---")) {
+        if let Ok(front) = serde_yaml::from_str::<serde_yaml::Value>(&contents[4..end + 4]) {
+            tags.extend(front.get("tags").map(list).unwrap_or_default());
+            if let Some(syntax) = front.get("syntax").and_then(text) {
+                let found = ps.find_syntax_by_token(&syntax).and_then(|syntax| syntax.file_extensions.first());
+                extension = found.cloned().unwrap_or(extension);
+            }
+        }
+        body = contents[end + 8..].trim_start_matches(|c| c != '\n');
+    }
+
+    let mut description: Vec<String> = Vec::new();
+    let mut lines: Vec<String> = Vec::new();
+    let mut commands = Vec::new();
+    for line in body.lines() {
+        let comment = line.trim_start().strip_prefix('#');
+        if (comment.is_some() || line.trim().is_empty()) && !lines.is_empty() {
+            let subject = if description.is_empty() { None } else { Some(description.join(" ")) };
+            commands.push(command(subject, &lines, tags.clone(), &extension));
+            description.clear();
+            lines.clear();
+        }
+
+        match comment {
+            Some(text) => description.push(text.trim().to_string()),
+            None if !line.trim().is_empty() => lines.push(line.to_string()),
+            None => (),
+        }
+    }
+    if !lines.is_empty() {
+        let subject = if description.is_empty() { None } else { Some(description.join(" ")) };
+        commands.push(command(subject, &lines, tags, &extension));
+    }
+
+    commands
+}
+
+// Return a Command of lines, with the first line as subject when there is no
+// description
+fn command(description: Option<String>, lines: &[String], tags: Vec<String>, extension: &str) -> Command {
+    let subject = description
+        .map(|text| text.trim_end_matches(':').trim().to_string())
+        .filter(|text| !text.is_empty())
+        .unwrap_or_else(|| lines[0].trim().to_string());
+    Command {
+        subject,
+        tags,
+        extension: extension.to_string(),
+        data: lines.iter().map(|line| format!("{}\n", line)).collect(),
+    }
+}
+
+// Replace the tldr placeholders in line with placeholders of the run
+// subcommand. A placeholder not starting with a letter, such as {{8080}},
+// becomes a numbered value with the text as default.
+fn tldr_placeholders(line: &str) -> String {
+    let mut values = 0;
+    TLDR_PLACEHOLDER.replace_all(line, |caps: &Captures| {
+        let text = &caps[1];
+        if let Some(option) = TLDR_OPTION.captures(text) {
+            return option[1].to_string();
+        }
+
+        let name = text.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<&str>>()
+            .join("_");
+        if name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            format!("{{{{{}}}}}", name)
+        } else {
+            values += 1;
+            format!("{{{{value{}:{}}}}}", values, text.replace('}', ""))
+        }
+    }).to_string()
+}
+
+// Read the note in file
+fn read_note(file: &path::Path) -> Note {
+    let contents = crate::helpers::read_to_string(&file.to_string_lossy()).replace("\r\n", "\n");
+    note(&contents, file)
+}
+
+// Return the note in contents, separating the front matter from the body
+fn note(contents: &str, file: &path::Path) -> Note {
+    let mut note = Note {
+        subject: None,
+        tags: None,
+        created: None,
+        last_updated: None,
+        body: contents.to_string(),
+    };
+
+    // The front matter is the YAML between the first two --- lines
+    let end = match contents.strip_prefix("---\n").and_then(|rest| rest.find("\n---")) {
+        Some(end) => end + 4,
+        None      => return note,
+    };
+    let front: serde_yaml::Value = match serde_yaml::from_str(&contents[4..end]) {
+        Ok(front) => front,
+        Err(error) => {
+            eprintln!("Warning: ignoring front matter of {}\n{}", file.display(), error);
+            return note;
+        },
+    };
+    let body_start = contents[end + 4..].find('\n').map(|n| end + 5 + n).unwrap_or(contents.len());
+    note.body = contents[body_start..].to_string();
+
+    let field = |names: &[&str]| names.iter().find_map(|name| front.get(*name)).cloned();
+    note.subject = field(&["subject", "title"]).and_then(|v| text(&v));
+    note.tags = field(&["tags"]).map(|v| list(&v));
+    note.created = field(&["created", "date"]).and_then(|v| text(&v)).and_then(|t| datetime(&t));
+    note.last_updated = field(&["last_updated", "updated", "modified"]).and_then(|v| text(&v)).and_then(|t| datetime(&t));
+    note
+}
+
+// Return the subject of note and the body without the heading used as
+// subject. The subject falls back to the first heading, then the file name.
+fn subject(note: &Note, file: &path::Path) -> (String, String) {
+    let body = note.body.trim_start_matches('\n');
+    let (first, rest) = body.split_once('\n').unwrap_or((body, ""));
+    let heading = first.strip_prefix("# ").map(|h| h.trim().to_string());
+
+    match (&note.subject, heading) {
+        (Some(subject), Some(heading)) if *subject == heading => (heading, rest.to_string()),
+        (Some(subject), _)                                    => (subject.to_string(), body.to_string()),
+        (None, Some(heading))                                 => (heading, rest.to_string()),
+        (None, None) => {
+            let stem = file.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+            (stem, body.to_string())
+        },
+    }
+}
+
+// Return the body as Tip data: the code of a single fenced block, followed
+// by parts, or else the body as Markdown.
+fn content(body: &str, ps: &syntect::parsing::SyntaxSet) -> Content {
+    let lines: Vec<&str> = body.trim_matches('\n').lines().collect();
+    let markdown = Content {
+        data: format!("{}\n", body.trim_matches('\n')),
+        extension: Some("md".to_string()),
+        sections: Vec::new(),
+    };
+
+    let (language, data, mut next) = match fence(&lines, 0) {
+        Some(block) => block,
+        None        => return markdown,
+    };
+
+    let mut sections = Vec::new();
+    loop {
+        next = skip_blank(&lines, next);
+        if next == lines.len() {
+            break;
+        }
+
+        // A part name can not hold white space, see part.rs
+        let name = match lines[next].strip_prefix("## ") {
+            Some(name) if !name.trim().contains(char::is_whitespace) => name.trim(),
+            _                                                        => return markdown,
+        };
+
+        // The attachments listed by the export can not be imported
+        let start = skip_blank(&lines, next + 1);
+        if name == "Attachments" && lines[start..].iter().all(|l| l.starts_with("- ") || l.is_empty()) {
+            break;
+        }
+
+        match fence(&lines, start) {
+            Some((extension, data, end)) => {
+                sections.push(crate::part::Section { name: name.to_string(), extension, data });
+                next = end;
+            },
+            None => return markdown,
+        }
+    }
+
+    let extension = language.or_else(|| crate::detect::extension(&data, ps));
+    Content { data, extension, sections }
+}
+
+// Return the language, contents and the following line of the fenced block
+// starting at line start, None if there is none.
+fn fence(lines: &[&str], start: usize) -> Option<(Option<String>, String, usize)> {
+    let line = lines.get(start)?;
+    let ticks = line.len() - line.trim_start_matches('`').len();
+    if ticks < 3 {
+        return None;
+    }
+
+    let fence = &line[..ticks];
+    let language = line[ticks..].trim();
+    let end = (start + 1..lines.len()).find(|n| lines[*n].trim_end() == fence)?;
+    let contents: String = lines[start + 1..end].iter().map(|l| format!("{}\n", l)).collect();
+    let language = if language.is_empty() { None } else { Some(language.to_string()) };
+
+    Some((language, contents, end + 1))
+}
+
+// Return the first line from start that is not blank
+fn skip_blank(lines: &[&str], start: usize) -> usize {
+    (start..lines.len()).find(|n| !lines[*n].trim().is_empty()).unwrap_or(lines.len())
+}
+
+// Return a YAML scalar as text
+fn text(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(text) => Some(text.to_string()),
+        serde_yaml::Value::Number(number) => Some(number.to_string()),
+        serde_yaml::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+// Return a YAML list, or a text of words separated by commas or spaces, as
+// a list of tags. A leading # (as in Obsidian) is removed.
+fn list(value: &serde_yaml::Value) -> Vec<String> {
+    let words: Vec<String> = match value {
+        serde_yaml::Value::Sequence(values) => values.iter().filter_map(text).collect(),
+        value => text(value)
+            .map(|text| text.split(|c: char| c == ',' || c.is_whitespace()).map(String::from).collect())
+            .unwrap_or_default(),
+    };
+
+    words.iter()
+        .map(|word| word.trim().trim_start_matches('#').to_string())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+// Parse a date as written in front matter, RFC 3339 or a local date with an
+// optional time.
+fn datetime(text: &str) -> Option<DateTime<Local>> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(text) {
+        return Some(datetime.with_timezone(&Local));
+    }
+
+    let naive = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"].iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| NaiveDate::parse_from_str(text, "%Y-%m-%d").ok().and_then(|d| d.and_hms_opt(0, 0, 0)))?;
+    Local.from_local_datetime(&naive).earliest()
+}
+
+// Return the hash of the contents of every Tip, with a description of the
+// Tip, to find duplicates
+fn known() -> HashMap<String, String> {
+    crate::tips::Tips::load().tips.iter()
+        .map(|tip| (hash(&crate::part::join(&tip.get_data(), &tip.parts)),
+                    format!("tip {}", tip.metadata.id.unwrap_or(0))))
+        .collect()
+}
+
+// Return the hash of data, ignoring trailing white space
+fn hash(data: &str) -> String {
+    format!("{:x}", Sha256::digest(data.trim_end().as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tldr_placeholders_are_converted() {
+        assert_eq!(tldr_placeholders("tar cf {{path/to/target.tar}} {{path/to/file}}"),
+                   "tar cf {{path_to_target_tar}} {{path_to_file}}");
+        assert_eq!(tldr_placeholders("nc -l {{8080}} {{1.2.3.4}}"), "nc -l {{value1:8080}} {{value2:1.2.3.4}}");
+        assert_eq!(tldr_placeholders("ls {{[-a|--all]}} {{[-l|--long]}}"), "ls --all --long");
+        assert_eq!(tldr_placeholders("echo plain"), "echo plain");
+    }
+
+    #[test]
+    fn tldr_page_commands() {
+        let page = "# tar\n\n> Archiving utility.\n\n\
+                    - [c]reate an archive:\n\n`tar {{[-c|--create]}} {{[-f|--file]}} {{target.tar}}`\n\n\
+                    `tar tf {{source.tar}}`\n";
+        let commands = tldr_page("file", page);
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0].subject, "Create an archive");
+        assert_eq!(commands[0].data, "tar --create --file {{target_tar}}\n");
+        assert_eq!(commands[0].tags, vec!["tar"]);
+        assert_eq!(commands[0].extension, COMMAND_EXTENSION);
+        assert_eq!(commands[1].subject, "tar tf {{source_tar}}");
+    }
+
+    #[test]
+    fn navi_sheet_commands() {
+        let sheet = "% git, vcs\n\n# Checkout a branch\ngit checkout <branch>\n$ branch: git branch\n\n\
+                     # Commit\ngit add .\ngit commit\n\n% docker\n\ndocker ps\n";
+        let commands = navi_sheet("sheet", sheet);
+        assert_eq!(commands.len(), 3);
+        assert_eq!(commands[0].subject, "Checkout a branch");
+        assert_eq!(commands[0].data, "git checkout {{branch}}\n");
+        assert_eq!(commands[0].tags, vec!["git", "vcs"]);
+        assert_eq!(commands[1].subject, "Commit");
+        assert_eq!(commands[1].data, "git add .\ngit commit\n");
+        assert_eq!(commands[2].subject, "docker ps");
+        assert_eq!(commands[2].tags, vec!["docker"]);
+    }
+
+    #[test]
+    fn cheat_sheet_commands() {
+        let ps = syntect::parsing::SyntaxSet::load_defaults_newlines();
+        let sheet = "---\nsyntax: python\ntags: [ lang ]\n---\n\
+                     # Print a line\n# to standard output\nprint('hello')\n\nimport os\nos.getcwd()\n";
+        let commands = cheat_sheet("python", sheet, &ps);
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0].subject, "Print a line to standard output");
+        assert_eq!(commands[0].data, "print('hello')\n");
+        assert_eq!(commands[0].tags, vec!["python", "lang"]);
+        assert_eq!(commands[0].extension, "py");
+        assert_eq!(commands[1].subject, "import os");
+        assert_eq!(commands[1].data, "import os\nos.getcwd()\n");
+
+        let commands = cheat_sheet("ls", "# List all files\nls -a\n", &ps);
+        assert_eq!(commands[0].tags, vec!["ls"]);
+        assert_eq!(commands[0].extension, COMMAND_EXTENSION);
+    }
+
+    #[test]
+    fn note_front_matter() {
+        let file = path::Path::new("notes/fallback.md");
+        let parsed = note("---\ntitle: Greeting\ntags: \"#shell, demo\"\ndate: 2021-03-04\n---\n# Greeting\n\nSay hello\n", file);
+        assert_eq!(parsed.subject.as_deref(), Some("Greeting"));
+        assert_eq!(parsed.tags, Some(vec!["shell".to_string(), "demo".to_string()]));
+        assert_eq!(parsed.created, datetime("2021-03-04 00:00:00"));
+        assert_eq!(parsed.last_updated, None);
+        assert_eq!(parsed.body, "# Greeting\n\nSay hello\n");
+        assert_eq!(subject(&parsed, file), ("Greeting".to_string(), "\nSay hello\n".to_string()));
+
+        let parsed = note("No front matter\n", file);
+        assert_eq!(parsed.subject, None);
+        assert_eq!(parsed.tags, None);
+        assert_eq!(parsed.body, "No front matter\n");
+        assert_eq!(subject(&parsed, file), ("fallback".to_string(), "No front matter\n".to_string()));
+    }
+
+    #[test]
+    fn fenced_blocks() {
+        let lines = ["````rust", "```", "````", "after"];
+        assert_eq!(fence(&lines, 0), Some((Some("rust".to_string()), "```\n".to_string(), 3)));
+        assert_eq!(fence(&["```", "unclosed"], 0), None);
+        assert_eq!(fence(&["text"], 0), None);
+    }
+
+    #[test]
+    fn note_content() {
+        let ps = syntect::parsing::SyntaxSet::load_defaults_newlines();
+        let body = "\n```sh\nls\n```\n\n## test.py\n\n```py\nprint(1)\n```\n\n## Attachments\n\n- [a.png](a.png)\n";
+        let parsed = content(body, &ps);
+        assert_eq!(parsed.data, "ls\n");
+        assert_eq!(parsed.extension.as_deref(), Some("sh"));
+        assert_eq!(parsed.sections.len(), 1);
+        assert_eq!(parsed.sections[0].name, "test.py");
+        assert_eq!(parsed.sections[0].extension.as_deref(), Some("py"));
+        assert_eq!(parsed.sections[0].data, "print(1)\n");
+
+        let body = "```sh\nls\n```\n\nSome text\n";
+        let parsed = content(body, &ps);
+        assert_eq!(parsed.data, body);
+        assert_eq!(parsed.extension.as_deref(), Some("md"));
+        assert!(parsed.sections.is_empty());
+    }
+}
@@ -11,6 +11,8 @@ lazy_static::lazy_static! {
         tips.insert("tipsrc", format!("{}/.tipsrc", env::var("HOME").unwrap()));
         tips.insert("tips", format!("{}/.tips", env::var("HOME").unwrap()));
         tips.insert("data", format!("{}/data", &tips["tips"]));
+        tips.insert("syntaxes", format!("{}/syntaxes", &tips["tips"]));
+        tips.insert("themes", format!("{}/themes", &tips["tips"]));
         tips.insert("db_file", format!("{}/db.yaml", &tips["tips"]));
         tips.insert("tmp_file", format!("{}/tmp_file.yaml", &tips["tips"]));
         tips.insert("editor", "/usr/bin/vim".to_string());
@@ -20,15 +22,32 @@ lazy_static::lazy_static! {
 
 // This function will look for the ~/.tipsrc file. If not found the needed
 // files and paths will be created. If it is found a validation of config
-// and file structure will be performed.
-pub fn init() {
+// and file structure will be performed. With git the tips home is made a git
+// repository, also for an existing setup (see git.rs).
+pub fn init(git: bool) {
     if path::Path::new(&TIPS.get("tipsrc").unwrap()).exists() {
-        verify_existing()
+        if git {
+            enable_git()
+        } else {
+            verify_existing()
+        }
     } else {
-        create()
+        create(git)
     };
 }
 
+// Enable git mode for an existing setup
+fn enable_git() {
+    let mut tipsrc = crate::config::Config::load();
+    crate::git::init_repository(&tipsrc.home());
+
+    if !tipsrc.git {
+        tipsrc.git = true;
+        tipsrc.store(TIPS.get("tipsrc").unwrap());
+    }
+    println!("Git mode enabled in {}", tipsrc.home().display());
+}
+
 // Verify that existing ~/.tiprc is valid and that the file structure
 // exists.
 //
@@ -38,7 +57,7 @@ fn verify_existing() {
 }
 
 // Create needed file structure, tipsrc and database
-fn create() {
+fn create(git: bool) {
 
     // Let user know what files and directories will be created
     // and let user answer before creating anything
@@ -46,6 +65,8 @@ fn create() {
     println!("\tfile      {}", TIPS.get("tipsrc").unwrap());
     println!("\tdirectory {}", TIPS.get("tips").unwrap());
     println!("\tdirectory {}", TIPS.get("data").unwrap());
+    println!("\tdirectory {}", TIPS.get("syntaxes").unwrap());
+    println!("\tdirectory {}", TIPS.get("themes").unwrap());
     print!("Go ahead and create them ? [y/n]: ");
 
     match io::stdout().flush() {
@@ -75,23 +96,30 @@ fn create() {
     };
 
     create_directory();
-    create_tipsrc();
+    create_tipsrc(git);
     create_database();
+
+    if git {
+        crate::git::init_repository(path::Path::new(TIPS.get("tips").unwrap()));
+    }
 }
 
 // Create all directories needed for Tips
 fn create_directory() {
     crate::helpers::create_dir_all(TIPS.get("tips").unwrap());
     crate::helpers::create_dir_all(TIPS.get("data").unwrap());
+    crate::helpers::create_dir_all(TIPS.get("syntaxes").unwrap());
+    crate::helpers::create_dir_all(TIPS.get("themes").unwrap());
 }
 
 // Create a Config struct and store it. This becomes ~/.tipsrc
-fn create_tipsrc() {
+fn create_tipsrc(git: bool) {
     let tipsrc = crate::config::Config {
         db_file:     TIPS.get("db_file").unwrap().to_string(),
         tmp_file:    TIPS.get("tmp_file").unwrap().to_string(),
         data:        TIPS.get("data").unwrap().to_string(),
         editor:      TIPS.get("editor").unwrap().to_string(),
+        pager:       None,
 
         style: crate::config::Style {
 
@@ -101,13 +129,24 @@ fn create_tipsrc() {
                 id: "".to_string(),
                 subject: "".to_string(),
                 tags: "".to_string(),
+                created: "".to_string(),
+                updated: "".to_string(),
+                ext: "".to_string(),
+                size: "".to_string(),
+                uuid: "".to_string(),
+                align: std::collections::HashMap::new(),
             },
 
             // Let's expect that everyone runs Solarized dark theme... I do
             data: crate::config::DataStyle {
                 theme: "Solarized (dark)".to_string(),
+                render: vec!["md".to_string()],
             },
         },
+
+        list: crate::config::ListConfig::default(),
+        git,
+        sync:        None,
     };
 
     tipsrc.store(TIPS.get("tipsrc").unwrap());
@@ -121,7 +160,9 @@ fn create_database() {
     introduction_tip_data(&uuid);
 
     // Instantiate a Tips struct with a 'welcome tip'
-    let tips = crate::tips::Tips {
+    let mut tips = crate::tips::Tips {
+        last_id: 0,
+        aliases: std::collections::BTreeMap::new(),
         tips: vec![
             crate::tip::Tip {
                 metadata: crate::metadata::Metadata {
@@ -133,6 +174,8 @@ fn create_database() {
                     data_extension: Some(String::from("txt")),
                 },
                 data: uuid,
+                parts: Vec::new(),
+                attachments: Vec::new(),
             }
         ]
     };
@@ -1,24 +1,41 @@
 // This file contains functionality to list a Tip or all Tips
 
+use crate::format::{Format};
+
 
 // Entry point for list subcommand
 pub fn list(matches: &clap::ArgMatches) {
+    let format = Format::from_matches(matches);
+    let tips = crate::tips::Tips::load();
 
     // List all tips or if pattern vas given list those matching
-    if let Some(pattern) = matches.value_of("pattern") {
-         match_pattern(pattern, matches.value_of("source"));
-    } else {
-        crate::tips::Tips::summary();
+    let found: Vec<&crate::tip::Tip> = match matches.value_of("pattern") {
+        Some(pattern) => match_pattern(&tips, pattern, matches.value_of("source")),
+        None          => tips.tips.iter().collect(),
+    };
+
+    // Present the result
+    match format {
+        Format::Table => {
+            if found.is_empty() {
+                if let Some(pattern) = matches.value_of("pattern") {
+                    println!("No t(ips) found using pattern: {}", pattern);
+                    std::process::exit(1);
+                }
+            }
+            let columns = crate::columns::selected(matches);
+            crate::present::present(&crate::columns::rows(&found, &columns));
+        },
+        _ => crate::format::output(&found, &format, matches.is_present("content")),
     }
 }
 
 // Search for pattern among all tips. Unless specified all components are
 // searched, but if given only search that component.
-fn match_pattern(pattern: &str, part: Option<&str>) {
-
-    // Load tips & initialize empty vector that will hold potential Tip matches
-    let tips = crate::tips::Tips::load();
-    let mut rows = Vec::new();
+fn match_pattern<'a>(
+    tips: &'a crate::tips::Tips,
+    pattern: &str,
+    part: Option<&str>) -> Vec<&'a crate::tip::Tip> {
 
     // Set component according to "part" arg
     let component = match part {
@@ -29,20 +46,8 @@ fn match_pattern(pattern: &str, part: Option<&str>) {
         _               => panic!("Part not implemented!"),
     };
 
-    // Iterate over all tips and populate the rows vector with tip headlines
-    // for any Tip that matches the pattern.
-    for tip in tips.tips.iter() {
-        if let Some(tip) = crate::query::search(pattern, &tip, &component) {
-            rows.push(tip.header_cells())
-        }
-    };
-
-    // Present the result
-    match rows.len() {
-        0 => {
-            println!("No t(ips) found using pattern: {}", pattern);
-            std::process::exit(1);
-        },
-        _ => crate::present::present(&rows),
-    }
+    // Iterate over all tips and collect any Tip that matches the pattern.
+    tips.tips.iter()
+        .filter_map(|tip| crate::query::search(pattern, tip, &component))
+        .collect()
 }
@@ -0,0 +1,32 @@
+// This file contains functionality to list the revisions of a Tip (log
+// subcommand). See revision.rs for how revisions are recorded.
+
+
+// Entry point for log subcommand
+pub fn log(matches: &clap::ArgMatches) {
+
+    // Since arg "id" is required it is safe to call unwrap here
+    let tips = crate::tips::Tips::load();
+    let tip = &tips.tips[tips.find(matches.value_of("id").unwrap())];
+    let id = tip.metadata.id.unwrap_or(0);
+
+    let revisions = crate::revision::load(tip);
+    if revisions.is_empty() {
+        println!("No revisions of tip {}", id);
+        return;
+    }
+
+    // List the revisions newest first, marking the one matching the Tip
+    let current = crate::revision::Revision::of(tip, 0, "").text();
+    let width = revisions.iter().map(|r| r.message.len()).max().unwrap_or(0);
+    for revision in revisions.iter().rev() {
+        let marker = if revision.text() == current { "*" } else { " " };
+        println!("{} {:>3}  {}  {:<width$}  {}",
+                 marker,
+                 revision.revision,
+                 revision.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                 revision.message,
+                 revision.subject,
+                 width = width);
+    }
+}
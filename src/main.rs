@@ -26,13 +26,53 @@ mod list;
 mod present;
 mod statics;
 mod open;
+mod format;
+mod columns;
+mod pager;
+mod markdown;
+mod assets;
+mod themes;
+mod syntaxes;
+mod detect;
+mod color;
+mod filter;
+mod clipboard;
+mod browser;
+mod pick;
+mod run;
+mod record;
+mod history;
+mod part;
+mod attachment;
+mod attach;
+mod revision;
+mod log;
+mod diff;
+mod restore;
+mod trash;
+mod git;
+mod sync;
+mod renumber;
+mod export;
+mod import;
+mod backup;
+mod site;
 
 
-use clap::{App, Arg, SubCommand, crate_version};
+use clap::{App, AppSettings, Arg, SubCommand, crate_version};
 
 fn main() {
     let matches = App::new("tips")
         .version(crate_version!())
+        .arg(
+            Arg::with_name("color")
+                .help("When to use colors")
+                .long("color")
+                .global(true)
+                .takes_value(true)
+                .default_value("auto")
+                .possible_values(crate::color::CHOICES),
+        )
         .subcommand(
             SubCommand::with_name("add")
                 .display_order(1)
@@ -52,6 +92,15 @@ fn main() {
                         .long("file")
                         .takes_value(true),
                 )
+                .arg(
+                    Arg::with_name("from-history")
+                        .help("Pick commands from the last N (default 100) in the shell history")
+                        .long("from-history")
+                        .value_name("N")
+                        .takes_value(true)
+                        .min_values(0)
+                        .conflicts_with("file"),
+                )
         )
         .subcommand(
             SubCommand::with_name("list")
@@ -74,6 +123,25 @@ fn main() {
                         .default_value_if("pattern", None, "*")
                         .possible_values(&["subject", "tag", "data", "date", "*"]),
                 )
+                .arg(
+                    Arg::with_name("format")
+                        .help("Output format")
+                        .long("format")
+                        .takes_value(true)
+                        .default_value("table")
+                        .possible_values(crate::format::FORMATS),
+                )
+                .arg(
+                    Arg::with_name("content")
+                        .help("Include the tip data in json, yaml, csv and tsv output")
+                        .long("content"),
+                )
+                .arg(
+                    Arg::with_name("columns")
+                        .help("Comma separated columns: id,subject,tags,created,updated,ext,size")
+                        .long("columns")
+                        .takes_value(true),
+                )
         )
         .subcommand(
             SubCommand::with_name("show")
@@ -82,9 +150,38 @@ fn main() {
                 .about("show a t(ips)")
                 .arg(
                     Arg::with_name("id")
-                        .help("t(ips) id")
+                        .help("t(ips) id or uuid prefix, optionally followed by a part name as in 12:manifest")
                         .required(true),
                 )
+                .arg(
+                    Arg::with_name("format")
+                        .help("Output format")
+                        .long("format")
+                        .takes_value(true)
+                        .default_value("table")
+                        .possible_values(crate::format::FORMATS),
+                )
+                .arg(
+                    Arg::with_name("no-content")
+                        .help("Exclude the tip data from json, yaml, csv and tsv output")
+                        .long("no-content"),
+                )
+                .arg(
+                    Arg::with_name("no-pager")
+                        .help("Do not page output that exceeds the terminal height")
+                        .long("no-pager"),
+                )
+                .arg(
+                    Arg::with_name("render")
+                        .help("Render the tip data as Markdown")
+                        .long("render")
+                        .conflicts_with("raw"),
+                )
+                .arg(
+                    Arg::with_name("raw")
+                        .help("Do not render the tip data as Markdown")
+                        .long("raw"),
+                )
         )
         .subcommand(
             SubCommand::with_name("update")
@@ -93,7 +190,7 @@ fn main() {
                 .about("Update todo")
                 .arg(
                     Arg::with_name("id")
-                        .help("Update t(ips) id")
+                        .help("Update t(ips) id or uuid prefix")
                         .takes_value(true)
                         .required(true)
             ),
@@ -102,26 +199,384 @@ fn main() {
             SubCommand::with_name("remove")
                 .display_order(5)
                 .visible_alias("r")
-                .about("Remove a t(ip)")
+                .about("Remove a t(ip), moving it to the trash")
                 .arg(
                     Arg::with_name("id")
-                        .help("t(ips) id")
+                        .help("t(ips) id or uuid prefix")
                         .takes_value(true)
                         .required(true),
                 )
+                .arg(
+                    Arg::with_name("force")
+                        .help("Remove the tip permanently instead of moving it to the trash")
+                        .long("force")
+                        .short("f"),
+                )
         )
         .subcommand(
             SubCommand::with_name("init")
                 .display_order(6)
-                .about("Initialize t(ips)"),
+                .about("Initialize t(ips)")
+                .arg(
+                    Arg::with_name("git")
+                        .help("Keep the tips in a git repository, committing every change")
+                        .long("git"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("tui")
+                .display_order(7)
+                .about("Browse tips in an interactive terminal UI"),
+        )
+        .subcommand(
+            SubCommand::with_name("pick")
+                .display_order(8)
+                .about("Pick a tip with an inline selector and print it")
+                .arg(
+                    Arg::with_name("print")
+                        .help("What to print of the picked tip")
+                        .long("print")
+                        .takes_value(true)
+                        .default_value("id")
+                        .possible_values(&["id", "data", "subject"]),
+                )
+                .arg(
+                    Arg::with_name("query")
+                        .help("Initial query"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("run")
+                .display_order(9)
+                .about("Run a tip as a shell command, prompting for placeholders")
+                .arg(
+                    Arg::with_name("id")
+                        .help("Id or uuid prefix of the tip to run")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("yes")
+                        .help("Run the command without asking for confirmation")
+                        .short("y")
+                        .long("yes"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("record")
+                .display_order(10)
+                .about("Run a command and add it with its output as a tip")
+                .setting(AppSettings::TrailingVarArg)
+                .arg(
+                    Arg::with_name("subject")
+                        .help("Subject of the tip, defaults to the command line")
+                        .long("subject")
+                        .short("s")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("no-edit")
+                        .help("Add the tip without opening the template in the editor")
+                        .long("no-edit"),
+                )
+                .arg(
+                    Arg::with_name("command")
+                        .help("Command to run and its arguments")
+                        .required(true)
+                        .multiple(true),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("attach")
+                .display_order(11)
+                .about("Attach a file to a tip")
+                .arg(
+                    Arg::with_name("id")
+                        .help("Id or uuid prefix of the tip to attach the file to")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("file")
+                        .help("File to attach")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("name")
+                        .help("Name of the attachment, defaults to the file name")
+                        .long("name")
+                        .short("n")
+                        .takes_value(true),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("attachment")
+                .display_order(12)
+                .about("Get or remove an attachment of a tip")
+                .subcommand(
+                    SubCommand::with_name("get")
+                        .about("Write the attachment to a file, or to stdout")
+                        .arg(
+                            Arg::with_name("id")
+                                .help("Id or uuid prefix of the tip")
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::with_name("name")
+                                .help("Name of the attachment")
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::with_name("output")
+                                .help("File to write the attachment to")
+                                .long("output")
+                                .short("o")
+                                .takes_value(true),
+                        )
+                )
+                .subcommand(
+                    SubCommand::with_name("remove")
+                        .about("Remove the attachment from the tip")
+                        .arg(
+                            Arg::with_name("id")
+                                .help("Id or uuid prefix of the tip")
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::with_name("name")
+                                .help("Name of the attachment")
+                                .required(true),
+                        )
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("log")
+                .display_order(13)
+                .about("List the revisions of a tip")
+                .arg(
+                    Arg::with_name("id")
+                        .help("Id or uuid prefix of the tip")
+                        .required(true),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("diff")
+                .display_order(14)
+                .about("Show the changes between revisions of a tip")
+                .arg(
+                    Arg::with_name("id")
+                        .help("Id or uuid prefix of the tip")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("rev1")
+                        .help("Revision to compare, defaults to the previous revision"),
+                )
+                .arg(
+                    Arg::with_name("rev2")
+                        .help("Revision to compare with, defaults to the tip"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("restore")
+                .display_order(15)
+                .about("Restore a tip to one of its revisions")
+                .arg(
+                    Arg::with_name("id")
+                        .help("Id or uuid prefix of the tip")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("revision")
+                        .help("Revision to restore")
+                        .required(true),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("trash")
+                .display_order(16)
+                .about("List, restore or permanently remove removed tips")
+                .subcommand(
+                    SubCommand::with_name("list")
+                        .about("List the tips in the trash")
+                )
+                .subcommand(
+                    SubCommand::with_name("restore")
+                        .about("Restore a tip from the trash, with a new id if its id is taken")
+                        .arg(
+                            Arg::with_name("id")
+                                .help("Id or uuid prefix of the removed tip")
+                                .required(true),
+                        )
+                )
+                .subcommand(
+                    SubCommand::with_name("empty")
+                        .about("Permanently remove the tips in the trash")
+                        .arg(
+                            Arg::with_name("older-than")
+                                .help("Only remove tips removed longer ago than this, for example 30d, 12h or 2w")
+                                .long("older-than")
+                                .takes_value(true),
+                        )
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("git")
+                .display_order(17)
+                .about("Run git in the tips home (git mode)")
+                .setting(AppSettings::TrailingVarArg)
+                .setting(AppSettings::AllowLeadingHyphen)
+                .arg(
+                    Arg::with_name("args")
+                        .help("Arguments passed to git")
+                        .multiple(true),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("history")
+                .display_order(18)
+                .about("Show the commits of tip changes (git mode)")
+                .arg(
+                    Arg::with_name("id")
+                        .help("Only show the commits changing the tip with id or uuid prefix"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("sync")
+                .display_order(19)
+                .about("Sync the tips with a git repository or directory")
+                .arg(
+                    Arg::with_name("remote")
+                        .help("Git repository or directory to sync with, defaults to the sync config"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("renumber")
+                .display_order(20)
+                .about("Give tips sharing an id a new id, old ids are kept as aliases")
+                .arg(
+                    Arg::with_name("compact")
+                        .help("Number all tips 1, 2, 3 ... closing the gaps of removed tips")
+                        .long("compact"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("export")
+                .display_order(21)
+                .about("Export the tips")
+                .subcommand(
+                    SubCommand::with_name("markdown")
+                        .about("Write one Markdown file per tip and an index to a directory")
+                        .arg(
+                            Arg::with_name("dir")
+                                .help("Directory to write to")
+                                .required(true),
+                        )
+                )
+                .subcommand(
+                    SubCommand::with_name("html")
+                        .about("Write a static HTML site with search to a directory")
+                        .arg(
+                            Arg::with_name("dir")
+                                .help("Directory to write to")
+                                .required(true),
+                        )
+                )
+                .subcommand(
+                    SubCommand::with_name("json")
+                        .about("Write all tips and their contents as one JSON document, for backup")
+                        .arg(
+                            Arg::with_name("output")
+                                .help("File to write to, instead of stdout")
+                                .long("output")
+                                .short("o")
+                                .takes_value(true),
+                        )
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("import")
+                .display_order(22)
+                .about("Import tips")
+                .subcommand(
+                    SubCommand::with_name("markdown")
+                        .about("Add a tip for each Markdown file in a directory tree")
+                        .arg(
+                            Arg::with_name("dir")
+                                .help("Directory to read from")
+                                .required(true),
+                        )
+                )
+                .subcommand(
+                    SubCommand::with_name("json")
+                        .about("Restore tips from a document written by export json")
+                        .arg(
+                            Arg::with_name("file")
+                                .help("File to read, or - (the default) for stdin"),
+                        )
+                        .arg(
+                            Arg::with_name("merge")
+                                .help("Add the tips not in the database, keeping the others")
+                                .long("merge")
+                                .conflicts_with("replace"),
+                        )
+                        .arg(
+                            Arg::with_name("replace")
+                                .help("Replace the tips, moving tips not in the backup to the trash")
+                                .long("replace"),
+                        )
+                        .arg(
+                            Arg::with_name("dry-run")
+                                .help("Only show what would be imported")
+                                .long("dry-run")
+                                .short("n"),
+                        )
+                )
+                .subcommand(
+                    SubCommand::with_name("tldr")
+                        .about("Add a tip for each command in tldr pages")
+                        .arg(
+                            Arg::with_name("path")
+                                .help("Page or directory of pages to read from")
+                                .required(true),
+                        )
+                )
+                .subcommand(
+                    SubCommand::with_name("navi")
+                        .about("Add a tip for each command in navi cheat sheets")
+                        .arg(
+                            Arg::with_name("path")
+                                .help("Cheat sheet or directory of .cheat files to read from")
+                                .required(true),
+                        )
+                )
+                .subcommand(
+                    SubCommand::with_name("cheat")
+                        .about("Add a tip for each command in cheat sheets of cheat")
+                        .arg(
+                            Arg::with_name("path")
+                                .help("Cheat sheet or directory of cheat sheets to read from")
+                                .required(true),
+                        )
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("themes")
+                .display_order(23)
+                .about("List available themes"),
+        )
+        .subcommand(
+            SubCommand::with_name("syntaxes")
+                .display_order(24)
+                .about("List available syntaxes"),
         )
         .get_matches();
 
+    crate::color::init(&matches);
+
     // Only use init now
     use crate::init::{init};
     match matches.subcommand_name() {
         Some("init") => {
-            init();
+            init(matches.subcommand_matches("init").unwrap().is_present("git"));
             std::process::exit(0);
         },
         Some(_)      => (),
@@ -135,6 +590,22 @@ fn main() {
     use crate::show::{show};
     use crate::list::{list};
     use crate::add::{add};
+    use crate::themes::{themes};
+    use crate::syntaxes::{syntaxes};
+    use crate::browser::{browser};
+    use crate::pick::{pick};
+    use crate::run::{run};
+    use crate::record::{record};
+    use crate::attach::{attach, attachment};
+    use crate::log::{log};
+    use crate::diff::{diff};
+    use crate::restore::{restore};
+    use crate::trash::{trash};
+    use crate::git::{git, history};
+    use crate::sync::{sync};
+    use crate::renumber::{renumber};
+    use crate::export::{export};
+    use crate::import::{import};
 
     match matches.subcommand_name() {
         Some("add")    => add(matches.subcommand_matches("add").unwrap()),
@@ -142,6 +613,24 @@ fn main() {
         Some("update") => update(matches.subcommand_matches("update").unwrap()),
         Some("show")   => show(matches.subcommand_matches("show").unwrap()),
         Some("list")   => list(matches.subcommand_matches("list").unwrap()),
+        Some("tui")    => browser(matches.subcommand_matches("tui").unwrap()),
+        Some("pick")   => pick(matches.subcommand_matches("pick").unwrap()),
+        Some("run")    => run(matches.subcommand_matches("run").unwrap()),
+        Some("record") => record(matches.subcommand_matches("record").unwrap()),
+        Some("attach") => attach(matches.subcommand_matches("attach").unwrap()),
+        Some("attachment") => attachment(matches.subcommand_matches("attachment").unwrap()),
+        Some("log")    => log(matches.subcommand_matches("log").unwrap()),
+        Some("diff")   => diff(matches.subcommand_matches("diff").unwrap()),
+        Some("restore") => restore(matches.subcommand_matches("restore").unwrap()),
+        Some("trash")  => trash(matches.subcommand_matches("trash").unwrap()),
+        Some("git")    => git(matches.subcommand_matches("git").unwrap()),
+        Some("history") => history(matches.subcommand_matches("history").unwrap()),
+        Some("sync")   => sync(matches.subcommand_matches("sync").unwrap()),
+        Some("renumber") => renumber(matches.subcommand_matches("renumber").unwrap()),
+        Some("export") => export(matches.subcommand_matches("export").unwrap()),
+        Some("import") => import(matches.subcommand_matches("import").unwrap()),
+        Some("themes") => themes(matches.subcommand_matches("themes").unwrap()),
+        Some("syntaxes") => syntaxes(matches.subcommand_matches("syntaxes").unwrap()),
         None           => {
             println!("Woops! No subcommand given");
             std::process::exit(1);
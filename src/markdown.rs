@@ -0,0 +1,431 @@
+// This file contains functionality to render Markdown tip data for the
+// terminal. Headings, emphasis, lists, block quotes, tables and links are
+// formatted using ANSI escapes, and fenced code blocks are highlighted with
+// syntect using the language of the fence.
+
+use pulldown_cmark::{Alignment, CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag};
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Style, Theme};
+use syntect::parsing::SyntaxSet;
+use syntect::util::{LinesWithEndings};
+use unicode_width::{UnicodeWidthStr};
+
+
+// ANSI escapes used for the different Markdown elements
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const ITALIC: &str = "\x1b[3m";
+const UNDERLINE: &str = "\x1b[4m";
+const STRIKE: &str = "\x1b[9m";
+const DIM: &str = "\x1b[2m";
+const HEADING: &str = "\x1b[1;35m";
+const SUBHEADING: &str = "\x1b[1;34m";
+const CODE: &str = "\x1b[36m";
+const LINK: &str = "\x1b[4;34m";
+
+// Width of horizontal rules
+const RULE_WIDTH: usize = 40;
+
+lazy_static::lazy_static! {
+    // Regex matching the ANSI escapes written by the renderer
+    static ref ESCAPE: regex::Regex = regex::Regex::new("\x1b\\[[0-9;]*m").unwrap();
+}
+
+// Struct holding the state while rendering
+struct Renderer<'a> {
+
+    // The rendered output
+    out: String,
+
+    // Syntax set and theme used for fenced code blocks
+    ps: &'a SyntaxSet,
+    theme: &'a Theme,
+
+    // Stack of active ANSI styles, re-applied after a style ends
+    styles: Vec<&'static str>,
+
+    // Stack of lists, holding the next number for ordered lists
+    lists: Vec<Option<u64>>,
+
+    // Depth of block quotes
+    quotes: usize,
+
+    // Language and contents of the code block being rendered
+    code: Option<(String, String)>,
+
+    // Alignments and rows of the table being rendered
+    table: Option<(Vec<Alignment>, Vec<Vec<String>>)>,
+
+    // Url of the links being rendered
+    links: Vec<String>,
+
+    // True when nothing has been written on the current line
+    line_start: bool,
+
+    // Newlines ending the visible output, ignoring escapes and trailing
+    // spaces, None while nothing visible is written, and whether the visible
+    // output ends with spaces. Used by blank_line instead of scanning the
+    // output.
+    newlines: Option<usize>,
+    spaces: bool,
+}
+
+// Render Markdown data for the terminal
+pub fn render(data: &str, ps: &SyntaxSet, theme: &Theme) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+
+    let mut renderer = Renderer {
+        out: String::new(),
+        ps,
+        theme,
+        styles: Vec::new(),
+        lists: Vec::new(),
+        quotes: 0,
+        code: None,
+        table: None,
+        links: Vec::new(),
+        line_start: true,
+        newlines: None,
+        spaces: false,
+    };
+
+    for event in Parser::new_ext(data, options) {
+        renderer.event(event);
+    }
+
+    renderer.out.push_str(RESET);
+    renderer.out
+}
+
+impl<'a> Renderer<'a> {
+
+    // Functions
+
+    // Handle a single event from the Markdown parser
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag)           => self.start(tag),
+            Event::End(tag)             => self.end(tag),
+            Event::Text(text)           => self.text(&text),
+            Event::Code(text)           => {
+                self.push_style(CODE);
+                self.text(&text);
+                self.pop_style();
+            },
+            Event::Html(html)           => self.text(&html),
+            Event::FootnoteReference(r) => self.text(&format!("[^{}]", r)),
+            Event::SoftBreak            => self.newline(),
+            Event::HardBreak            => self.newline(),
+            Event::Rule                 => {
+                self.blank_line();
+                self.write(&format!("{}{}{}", DIM, "─".repeat(RULE_WIDTH), RESET));
+                self.end_line();
+            },
+            Event::TaskListMarker(done) => {
+                self.write(if done { "[x] " } else { "[ ] " });
+            },
+        }
+    }
+
+    // Handle the start of a Markdown element
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Paragraph => {
+                if self.lists.is_empty() {
+                    self.blank_line();
+                }
+            },
+            Tag::Heading(level, _, _) => {
+                self.blank_line();
+                self.push_style(match level {
+                    HeadingLevel::H1 => HEADING,
+                    HeadingLevel::H2 => SUBHEADING,
+                    _                => BOLD,
+                });
+                if level == HeadingLevel::H1 {
+                    self.push_style(UNDERLINE);
+                }
+            },
+            Tag::BlockQuote => {
+                self.blank_line();
+                self.quotes += 1;
+                self.push_style(ITALIC);
+            },
+            Tag::CodeBlock(kind) => {
+                let lang = match kind {
+                    CodeBlockKind::Fenced(lang) => lang.split_whitespace()
+                        .next()
+                        .unwrap_or("")
+                        .to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                self.blank_line();
+                self.code = Some((lang, String::new()));
+            },
+            Tag::List(start) => {
+                if self.lists.is_empty() {
+                    self.blank_line();
+                } else {
+                    self.end_line();
+                }
+                self.lists.push(start);
+            },
+            Tag::Item => {
+                self.end_line();
+                let depth = self.lists.len().saturating_sub(1);
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    },
+                    _ => "• ".to_string(),
+                };
+                self.write(&format!("{}{}", "  ".repeat(depth), marker));
+            },
+            Tag::FootnoteDefinition(name) => {
+                self.blank_line();
+                self.write(&format!("[^{}]: ", name));
+            },
+            Tag::Table(alignments) => {
+                self.blank_line();
+                self.table = Some((alignments, Vec::new()));
+            },
+            Tag::TableHead | Tag::TableRow => {
+                if let Some((_, rows)) = &mut self.table {
+                    rows.push(Vec::new());
+                }
+            },
+            Tag::TableCell => {
+                if let Some((_, rows)) = &mut self.table {
+                    if let Some(row) = rows.last_mut() {
+                        row.push(String::new());
+                    }
+                }
+            },
+            Tag::Emphasis      => self.push_style(ITALIC),
+            Tag::Strong        => self.push_style(BOLD),
+            Tag::Strikethrough => self.push_style(STRIKE),
+            Tag::Link(_, url, _) => {
+                self.links.push(url.to_string());
+                self.push_style(LINK);
+            },
+            Tag::Image(_, url, _) => {
+                self.links.push(url.to_string());
+                self.write("[image: ");
+            },
+        }
+    }
+
+    // Handle the end of a Markdown element
+    fn end(&mut self, tag: Tag) {
+        match tag {
+            Tag::Paragraph => self.end_line(),
+            Tag::Heading(level, _, _) => {
+                self.pop_style();
+                if level == HeadingLevel::H1 {
+                    self.pop_style();
+                }
+                self.end_line();
+            },
+            Tag::BlockQuote => {
+                self.pop_style();
+                self.quotes -= 1;
+                self.end_line();
+            },
+            Tag::CodeBlock(_) => {
+                if let Some((lang, code)) = self.code.take() {
+                    self.code_block(&lang, &code);
+                }
+            },
+            Tag::List(_) => {
+                self.lists.pop();
+                self.end_line();
+            },
+            Tag::Item => self.end_line(),
+            Tag::FootnoteDefinition(_) => self.end_line(),
+            Tag::Table(_) => {
+                if let Some((alignments, rows)) = self.table.take() {
+                    self.table(&alignments, &rows);
+                }
+            },
+            Tag::TableHead | Tag::TableRow | Tag::TableCell => (),
+            Tag::Emphasis | Tag::Strong | Tag::Strikethrough => self.pop_style(),
+            Tag::Link(_, _, _) => {
+                self.pop_style();
+                if let Some(url) = self.links.pop() {
+                    self.write(&format!(" {}({}){}", DIM, url, RESET));
+                    self.apply_styles();
+                }
+            },
+            Tag::Image(_, _, _) => {
+                if let Some(url) = self.links.pop() {
+                    self.write(&format!("]{}({}){}", DIM, url, RESET));
+                    self.apply_styles();
+                }
+            },
+        }
+    }
+
+    // Write text, or collect it if inside a code block or table cell
+    fn text(&mut self, text: &str) {
+        if let Some((_, code)) = &mut self.code {
+            code.push_str(text);
+            return;
+        }
+
+        if let Some((_, rows)) = &mut self.table {
+            if let Some(cell) = rows.last_mut().and_then(|row| row.last_mut()) {
+                cell.push_str(text);
+            }
+            return;
+        }
+
+        for (index, line) in text.split('\n').enumerate() {
+            if index > 0 {
+                self.newline();
+            }
+            self.write(line);
+        }
+    }
+
+    // Write a highlighted code block using the syntax of given language,
+    // falling back to plain text.
+    fn code_block(&mut self, lang: &str, code: &str) {
+        let syntax = match self.ps.find_syntax_by_token(lang) {
+            Some(syntax) if !lang.is_empty() => syntax,
+            _ => self.ps.find_syntax_plain_text(),
+        };
+
+        let mut h = HighlightLines::new(syntax, self.theme);
+        for line in LinesWithEndings::from(code) {
+            let ranges: Vec<(Style, &str)> = h.highlight(line, self.ps);
+            let escaped = crate::color::escaped(&ranges[..]);
+            self.write(&format!("  {}{}", escaped.trim_end_matches('\n'), RESET));
+            self.newline();
+        }
+    }
+
+    // Write a table with the columns padded to the widest cell
+    fn table(&mut self, alignments: &[Alignment], rows: &[Vec<String>]) {
+        let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        let widths: Vec<usize> = (0..columns)
+            .map(|col| rows.iter()
+                 .filter_map(|row| row.get(col))
+                 .map(|cell| cell.width())
+                 .max()
+                 .unwrap_or(0))
+            .collect();
+
+        for (index, row) in rows.iter().enumerate() {
+            let cells: Vec<String> = widths.iter().enumerate().map(|(col, width)| {
+                let cell = row.get(col).map(|c| c.as_str()).unwrap_or("");
+                let pad = width - cell.width();
+                match alignments.get(col) {
+                    Some(Alignment::Right)  => format!("{}{}", " ".repeat(pad), cell),
+                    Some(Alignment::Center) => format!(
+                        "{}{}{}", " ".repeat(pad / 2), cell, " ".repeat(pad - pad / 2)),
+                    _                       => format!("{}{}", cell, " ".repeat(pad)),
+                }
+            }).collect();
+
+            if index == 0 {
+                self.write(&format!("{}{}{}", BOLD, cells.join(" │ "), RESET));
+                self.newline();
+                let rule: Vec<String> = widths.iter().map(|w| "─".repeat(*w)).collect();
+                self.write(&rule.join("─┼─"));
+            } else {
+                self.write(&cells.join(" │ "));
+            }
+            self.newline();
+        }
+    }
+
+    // Write text, starting with the block quote prefix on a new line
+    fn write(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+
+        if self.line_start && self.quotes > 0 {
+            let prefix = format!("{}{}{}", RESET, "│ ".repeat(self.quotes), RESET);
+            self.push(&prefix);
+            self.apply_styles();
+        }
+
+        self.push(text);
+        self.line_start = false;
+    }
+
+    // Start a new line, indented to the current list item
+    fn newline(&mut self) {
+        self.out.push_str(RESET);
+        self.push("\n");
+        self.line_start = true;
+
+        if !self.lists.is_empty() {
+            self.push(&"  ".repeat(self.lists.len()));
+        }
+        self.apply_styles();
+    }
+
+    // End the current line unless nothing has been written on it
+    fn end_line(&mut self) {
+        if !self.line_start {
+            self.out.push_str(RESET);
+            self.push("\n");
+            self.line_start = true;
+        }
+    }
+
+    // Make sure there is an empty line before the next element. Escapes and
+    // indentation written after the last newline are not counted.
+    fn blank_line(&mut self) {
+        self.end_line();
+        if self.newlines.map(|newlines| newlines < 2).unwrap_or(false) {
+            self.push("\n");
+        }
+    }
+
+    // Write text to the output, counting the newlines ending the visible
+    // output
+    fn push(&mut self, text: &str) {
+        self.out.push_str(text);
+
+        let visible = ESCAPE.replace_all(text, "");
+        let core = visible.trim_end_matches(' ');
+        if core.is_empty() {
+            self.spaces = self.spaces || !visible.is_empty();
+            return;
+        }
+
+        let newlines = core.len() - core.trim_end_matches('\n').len();
+        self.newlines = match self.newlines {
+            Some(previous) if newlines == core.len() && !self.spaces => Some(previous + newlines),
+            _ => Some(newlines),
+        };
+        self.spaces = core.len() != visible.len();
+    }
+
+    // Activate style, keeping it until pop_style is called
+    fn push_style(&mut self, style: &'static str) {
+        self.styles.push(style);
+        self.out.push_str(style);
+    }
+
+    // Deactivate the latest style and re-apply the remaining
+    fn pop_style(&mut self) {
+        self.styles.pop();
+        self.out.push_str(RESET);
+        self.apply_styles();
+    }
+
+    // Write all active styles
+    fn apply_styles(&mut self) {
+        for style in self.styles.iter() {
+            self.out.push_str(style);
+        }
+    }
+}
@@ -7,7 +7,7 @@ use chrono::offset::Local;
 
 
 // Metadata struct
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Metadata {
 
     // The subject is supposed to be a ... subject ... for the string
@@ -0,0 +1,121 @@
+// This file contains functionality to page long output through a pager, such
+// as less, when stdout is a terminal.
+
+use crate::statics::{CONFIG};
+use std::io::{Write};
+use std::{env, path, process};
+
+
+// Pager used when neither the pager config nor $PAGER is set
+const DEFAULT_PAGER: &str = "less -R";
+
+// Write output to stdout, through the pager if paging is enabled, stdout is
+// a terminal and output does not fit the terminal height.
+pub fn page(output: &str, paging: bool) {
+    if paging && atty::is(atty::Stream::Stdout) && exceeds_terminal(output) {
+        if let Some(pager) = pager() {
+            if spawn(&pager, output) {
+                return;
+            }
+            eprintln!("Warning: could not run pager {}, writing to stdout", pager);
+        }
+    }
+
+    write(output);
+}
+
+// Return the pager command to use. The pager config takes precedence over
+// $PAGER, and an empty pager config disables paging.
+fn pager() -> Option<String> {
+    let pager = match &CONFIG.pager {
+        Some(pager) => pager.to_string(),
+        None => match env::var("PAGER") {
+            Ok(pager) => pager,
+            Err(_)    => DEFAULT_PAGER.to_string(),
+        },
+    };
+
+    if pager.trim().is_empty() {
+        None
+    } else {
+        Some(pager)
+    }
+}
+
+// Return true if output has more lines than the terminal height
+fn exceeds_terminal(output: &str) -> bool {
+    match terminal_size::terminal_size() {
+        Some((_, height)) => output.lines().count() >= height.0 as usize,
+        None              => false,
+    }
+}
+
+// Run the pager via the shell and write output to its stdin. The syntect
+// output contains ANSI escapes, so when the pager is less make sure it passes
+// them through (the -R option) unless the user configured $LESS with it
+// already. Returns false if the pager could not be run, so the output was not
+// shown.
+fn spawn(pager: &str, output: &str) -> bool {
+    let mut command = process::Command::new("sh");
+    command.arg("-c").arg(pager).stdin(process::Stdio::piped());
+
+    if is_less(pager) {
+        let less = match env::var("LESS") {
+            Ok(less) if raw_control_chars(&less) => less,
+            Ok(less)                             => format!("{} -R", less),
+            Err(_)                               => "-R".to_string(),
+        };
+        command.env("LESS", less.trim());
+    }
+
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(_)    => return false,
+    };
+
+    // The user may quit the pager before all output is written, so errors
+    // writing to the pager (broken pipe) are ignored.
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(output.as_bytes());
+    }
+
+    // The shell exits with 126 or 127 when the pager can not be executed or
+    // is not found. Other exit codes come from the pager itself.
+    match child.wait() {
+        Ok(status) => status.success() || !matches!(status.code(), Some(126) | Some(127)),
+        Err(error) => {
+            panic!("Error when waiting for pager {}\n{}", pager, error);
+        },
+    }
+}
+
+// Write output to stdout. A closed stdout (for example when piped to head)
+// is not an error.
+fn write(output: &str) {
+    let mut stdout = std::io::stdout();
+    match stdout.write_all(output.as_bytes()).and_then(|_| stdout.flush()) {
+        Ok(_) => (),
+        Err(ref error) if error.kind() == std::io::ErrorKind::BrokenPipe => (),
+        Err(error) => {
+            panic!("Error when trying to write to stdout\n{}", error)
+        },
+    };
+}
+
+// Return true if the pager command runs less
+fn is_less(pager: &str) -> bool {
+    pager.split_whitespace().next()
+        .and_then(|program| path::Path::new(program).file_name())
+        .map(|program| program == "less")
+        .unwrap_or(false)
+}
+
+// Return true if the less options hold -R (possibly grouped with other short
+// options, with or without dash) or --RAW-CONTROL-CHARS (or its -r
+// sibling --raw-control-chars, which passes the escapes too)
+fn raw_control_chars(less: &str) -> bool {
+    less.split_whitespace().any(|option| match option.strip_prefix("--") {
+        Some(long) => long.eq_ignore_ascii_case("raw-control-chars"),
+        None       => option.contains('R'),
+    })
+}
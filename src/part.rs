@@ -0,0 +1,178 @@
+// This file holds definition of a Tip's named data parts.
+//
+// Besides its data, a Tip may have named parts, for example a YAML manifest
+// and the shell commands using it. Each part has its own extension and is
+// stored in a separate file, just as the Tip data.
+//
+// In the template parts follow the Tip data, each starting with a part
+// separator line holding the name and optionally the extension:
+//
+//      ----- TIP DATA BELOW THIS LINE -----
+//      kubectl apply -f manifest.yaml
+//      ----- TIP PART: manifest yaml -----
+//      apiVersion: v1
+//      ...
+
+use crate::statics::{CONFIG};
+use serde::{Deserialize, Serialize};
+
+
+lazy_static::lazy_static! {
+    static ref SEPARATOR: regex::Regex = regex::Regex::new(
+        r"(?m)^----- TIP PART: (\S+)(?: (\S+))? -----$\n?"
+    ).unwrap();
+}
+
+// Struct describing a named part of a Tip's data
+#[derive(Serialize, Deserialize)]
+pub struct Part {
+
+    // The name of the part, unique within the Tip
+    pub name: String,
+
+    // Extension used to do syntax highlighting, same as data_extension
+    pub extension: Option<String>,
+
+    // The part data is stored in a separate file with a name same as this
+    // field.
+    pub data: uuid::Uuid,
+}
+
+impl Part {
+
+    // Functions
+
+    // Return the path of the data file
+    pub fn path(&self) -> String {
+        format!("{}/{}", &CONFIG.data, self.data)
+    }
+
+    // Return the contents of the data file
+    pub fn get_data(&self) -> String {
+        crate::helpers::read_to_string(&self.path())
+    }
+}
+
+// Struct holding a part as written in the template
+pub struct Section {
+    pub name: String,
+    pub extension: Option<String>,
+    pub data: String,
+}
+
+// Return the separator line starting the part name in the template
+pub fn separator(name: &str, extension: &Option<String>) -> String {
+    match extension {
+        Some(ext) if !ext.trim().is_empty() => format!("----- TIP PART: {} {} -----", name, ext),
+        _                                   => format!("----- TIP PART: {} -----", name),
+    }
+}
+
+// Split the data section of the template into the Tip data and its parts.
+// Will panic if a part name is used twice.
+pub fn split(data: &str) -> (String, Vec<Section>) {
+    let mut sections: Vec<Section> = Vec::new();
+    let mut main = None;
+    let mut start = 0;
+
+    for caps in SEPARATOR.captures_iter(data) {
+        let separator = caps.get(0).unwrap();
+        let contents = data[start..separator.start()].to_string();
+        match sections.last_mut() {
+            Some(section) => section.data = contents,
+            None          => main = Some(contents),
+        }
+
+        let name = caps[1].to_string();
+        if sections.iter().any(|section| section.name == name) {
+            panic!("Error part name {} is used more than once", name);
+        }
+
+        sections.push(Section {
+            name,
+            extension: caps.get(2).map(|m| m.as_str().to_string()),
+            data: String::new(),
+        });
+        start = separator.end();
+    }
+
+    let contents = data[start..].to_string();
+    match sections.last_mut() {
+        Some(section) => section.data = contents,
+        None          => main = Some(contents),
+    }
+
+    (main.unwrap_or_default(), sections)
+}
+
+// Join the Tip data and its parts as written in the template
+pub fn join(data: &str, parts: &[Part]) -> String {
+    let sections: Vec<Section> = parts.iter()
+        .map(|part| Section {
+            name: part.name.to_string(),
+            extension: part.extension.clone(),
+            data: part.get_data(),
+        })
+        .collect();
+
+    join_sections(data, &sections)
+}
+
+// Join the Tip data and the sections of its parts as written in the template
+pub fn join_sections(data: &str, sections: &[Section]) -> String {
+    let mut joined = data.to_string();
+
+    for section in sections {
+        if !joined.is_empty() && !joined.ends_with('\n') {
+            joined.push('\n');
+        }
+        joined.push_str(&separator(&section.name, &section.extension));
+        joined.push('\n');
+        joined.push_str(&section.data);
+    }
+
+    joined
+}
+
+// Undo what join_sections adds to the Tip data and parts as read back from
+// the template: the newline before a separator when the stored data, or the
+// stored data of the part with the same name, does not end with one.
+// Returns the Tip data.
+pub fn unjoin(stored: &str, parts: &[Part], data: String, sections: &mut [Section]) -> String {
+    let data = if sections.is_empty() { data } else { without_newline(stored, data) };
+
+    let last = sections.len().saturating_sub(1);
+    for section in sections.iter_mut().take(last) {
+        if let Some(part) = parts.iter().find(|part| part.name == section.name) {
+            let contents = std::mem::take(&mut section.data);
+            section.data = without_newline(&part.get_data(), contents);
+        }
+    }
+
+    data
+}
+
+// Return data without the newline ending it if that is the only difference
+// to stored
+fn without_newline(stored: &str, data: String) -> String {
+    if !stored.ends_with('\n') && data.strip_suffix('\n') == Some(stored) {
+        stored.to_string()
+    } else {
+        data
+    }
+}
+
+// Create the parts of the sections, writing their data files
+pub fn create(sections: Vec<Section>) -> Vec<Part> {
+    sections.into_iter()
+        .map(|section| {
+            let part = Part {
+                name: section.name,
+                extension: section.extension,
+                data: uuid::Uuid::new_v4(),
+            };
+            crate::helpers::write_to_file(&part.path(), &section.data);
+            part
+        })
+        .collect()
+}
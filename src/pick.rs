@@ -0,0 +1,298 @@
+// This file contains functionality to pick a Tip with an inline selector
+// (pick subcommand), meant to be bound to a key in the shell.
+//
+// The selector is drawn on stderr below the cursor, so stdout only receives
+// the picked Tip's id, subject or data. See README for shell bindings.
+//
+// The selector is also used to pick commands from the shell history, see
+// history.rs, where several lines may be marked and picked.
+
+use crate::filter::{Entry, Matching};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::{cursor, queue, style, terminal};
+use std::collections::{BTreeSet};
+use std::io::{Write};
+use std::{io, process};
+use unicode_width::{UnicodeWidthStr};
+
+
+// Maximum number of tips shown in the selector
+const MAX_HEIGHT: u16 = 10;
+
+// Exit code when the user cancels, same as fzf
+const CANCELLED: i32 = 130;
+
+// Struct holding the state of the selector
+pub struct Picker {
+
+    // Formatted line and filter text for each line to pick from
+    lines: Vec<String>,
+    entries: Vec<Entry>,
+
+    // The filter query and how it is matched
+    query: String,
+    matching: Matching,
+
+    // Indexes of the lines matching the query and the selected position
+    visible: Vec<usize>,
+    selected: usize,
+
+    // If several lines may be picked, and the indexes of the marked lines
+    multiple: bool,
+    marked: BTreeSet<usize>,
+
+    // Number of lines used for tips and the terminal width
+    height: u16,
+    width: usize,
+}
+
+// Entry point for pick subcommand
+pub fn pick(matches: &clap::ArgMatches) {
+    let tips = crate::tips::Tips::load();
+    if tips.tips.is_empty() {
+        eprintln!("No t(ips) to pick from");
+        process::exit(1);
+    }
+
+    // Format the tips the same way as list, one line per Tip
+    let all: Vec<&crate::tip::Tip> = tips.tips.iter().collect();
+    let rows = crate::columns::rows(&all, &crate::columns::configured());
+    let lines = lines(&rows);
+
+    let entries: Vec<Entry> = tips.tips.iter().map(|tip| Entry::new(tip, true)).collect();
+    let query = matches.value_of("query").unwrap_or("");
+    let picked = Picker::new(lines, entries, query, false).run();
+
+    let tip = match picked.first() {
+        Some(index) => &tips.tips[*index],
+        None        => process::exit(CANCELLED),
+    };
+
+    match matches.value_of("print") {
+        Some("data")    => print!("{}", crate::part::join(&tip.get_data(), &tip.parts)),
+        Some("subject") => println!("{}", tip.metadata.subject),
+        _               => println!("{}", tip.metadata.id.unwrap()),
+    };
+}
+
+// Format rows as lines of text with aligned columns
+fn lines(rows: &[Vec<prettytable::Cell>]) -> Vec<String> {
+    let texts: Vec<Vec<String>> = rows.iter()
+        .map(|row| row.iter().map(|cell| cell.get_content()).collect())
+        .collect();
+
+    let mut widths: Vec<usize> = Vec::new();
+    for row in &texts {
+        for (column, text) in row.iter().enumerate() {
+            let width = text.width();
+            match widths.get_mut(column) {
+                Some(max) => *max = (*max).max(width),
+                None      => widths.push(width),
+            }
+        }
+    }
+
+    texts.iter()
+        .map(|row| {
+            row.iter().enumerate()
+                .map(|(column, text)| format!("{}{}", text, " ".repeat(widths[column] - text.width())))
+                .collect::<Vec<String>>()
+                .join(" | ")
+                .trim_end()
+                .to_string()
+        })
+        .collect()
+}
+
+impl Picker {
+
+    // Associated functions
+
+    // Create a Picker for lines, filtered by matching the query against
+    // entries. If multiple, several lines may be marked and picked.
+    pub fn new(lines: Vec<String>, entries: Vec<Entry>, query: &str, multiple: bool) -> Picker {
+        let (width, rows) = terminal::size().unwrap_or((80, 24));
+        let mut picker = Picker {
+            lines,
+            visible: (0..entries.len()).collect(),
+            entries,
+            query: query.to_string(),
+            matching: Matching::Fuzzy,
+            selected: 0,
+            multiple,
+            marked: BTreeSet::new(),
+            height: MAX_HEIGHT.min(rows.saturating_sub(2)).max(1),
+            width: width as usize,
+        };
+        picker.refilter();
+        picker
+    }
+
+    // Functions
+
+    // Run the selector until the user picks, returning the indexes of the
+    // picked lines: the marked lines or else the selected line. Returns an
+    // empty Vec if the user cancels.
+    pub fn run(&mut self) -> Vec<usize> {
+        let mut stderr = io::stderr();
+        if let Err(error) = terminal::enable_raw_mode() {
+            panic!("Error setting up terminal\n{}", error);
+        }
+
+        // Make room below the cursor, scrolling the terminal if needed
+        let setup = (0..=self.height).try_for_each(|_| write!(stderr, "\r\n"))
+            .and_then(|_| queue!(stderr, cursor::MoveUp(self.height + 1), cursor::SavePosition));
+        if let Err(error) = setup {
+            self.leave();
+            panic!("Error setting up terminal\n{}", error);
+        }
+
+        let picked = loop {
+            if let Err(error) = self.draw() {
+                self.leave();
+                panic!("Error drawing picker\n{}", error);
+            }
+
+            let key = match event::read() {
+                Ok(Event::Key(key)) => key,
+                Ok(_)               => continue,
+                Err(error) => {
+                    self.leave();
+                    panic!("Error reading terminal event\n{}", error);
+                },
+            };
+
+            match self.key(key) {
+                Some(picked) => break picked,
+                None         => continue,
+            }
+        };
+
+        self.leave();
+        picked
+    }
+
+    // Handle a key press. Returns Some when the selector is done, holding
+    // the picked indexes (empty if cancelled).
+    fn key(&mut self, key: KeyEvent) -> Option<Vec<usize>> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+        match key.code {
+            KeyCode::Esc                        => return Some(Vec::new()),
+            KeyCode::Char('c') if ctrl          => return Some(Vec::new()),
+            KeyCode::Char('g') if ctrl          => return Some(Vec::new()),
+            KeyCode::Enter                      => return Some(self.picked()),
+            KeyCode::Up                         => self.move_selection(-1),
+            KeyCode::Char('p') | KeyCode::Char('k') if ctrl => self.move_selection(-1),
+            KeyCode::Down                       => self.move_selection(1),
+            KeyCode::Char('n') | KeyCode::Char('j') if ctrl => self.move_selection(1),
+            KeyCode::Tab if self.multiple       => {
+                self.mark();
+                self.move_selection(1);
+            },
+            KeyCode::Char('r') if ctrl          => {
+                self.matching = self.matching.toggle();
+                self.refilter();
+            },
+            KeyCode::Tab                        => {
+                self.matching = self.matching.toggle();
+                self.refilter();
+            },
+            KeyCode::Backspace                  => {
+                self.query.pop();
+                self.refilter();
+            },
+            KeyCode::Char('u') if ctrl          => {
+                self.query.clear();
+                self.refilter();
+            },
+            KeyCode::Char(c) if !ctrl           => {
+                self.query.push(c);
+                self.refilter();
+            },
+            _ => (),
+        };
+
+        None
+    }
+
+    // Update the visible tips given the query. An invalid regex keeps the
+    // previous result.
+    fn refilter(&mut self) {
+        if let Ok(visible) = crate::filter::filter(&self.entries, &self.query, self.matching) {
+            self.visible = visible;
+            self.selected = 0;
+        }
+    }
+
+    // Return the marked indexes or else the selected index
+    fn picked(&self) -> Vec<usize> {
+        if !self.marked.is_empty() {
+            return self.marked.iter().copied().collect();
+        }
+
+        self.visible.get(self.selected).copied().into_iter().collect()
+    }
+
+    // Toggle the mark of the selected line
+    fn mark(&mut self) {
+        if let Some(index) = self.visible.get(self.selected) {
+            if !self.marked.remove(index) {
+                self.marked.insert(*index);
+            }
+        }
+    }
+
+    // Move the selection by offset
+    fn move_selection(&mut self, offset: isize) {
+        let last = self.visible.len().saturating_sub(1) as isize;
+        self.selected = (self.selected as isize + offset).max(0).min(last) as usize;
+    }
+
+    // Draw the prompt and the visible tips below the saved cursor position
+    fn draw(&self) -> io::Result<()> {
+        let mut stderr = io::stderr();
+        queue!(stderr, cursor::RestorePosition,
+               terminal::Clear(terminal::ClearType::FromCursorDown))?;
+
+        // The prompt with the query and number of matches
+        write!(stderr, "> {}", self.query)?;
+        queue!(stderr, style::SetAttribute(style::Attribute::Dim))?;
+        write!(stderr, "  {}/{} [{}]", self.visible.len(), self.lines.len(), self.matching.name())?;
+        if !self.marked.is_empty() {
+            write!(stderr, " ({} marked)", self.marked.len())?;
+        }
+        queue!(stderr, style::SetAttribute(style::Attribute::Reset))?;
+
+        // Scroll the list so the selection is always shown
+        let height = self.height as usize;
+        let first = (self.selected + 1).saturating_sub(height);
+
+        for (position, index) in self.visible.iter().enumerate().skip(first).take(height) {
+            let line = crate::columns::truncate(&self.lines[*index], self.width.saturating_sub(2));
+            let mark = if self.marked.contains(index) { '*' } else { ' ' };
+            write!(stderr, "\r\n")?;
+            if position == self.selected {
+                queue!(stderr, style::SetAttribute(style::Attribute::Reverse))?;
+                write!(stderr, ">{}{}", mark, line)?;
+                queue!(stderr, style::SetAttribute(style::Attribute::Reset))?;
+            } else {
+                write!(stderr, " {}{}", mark, line)?;
+            }
+        }
+
+        // Leave the cursor after the query
+        queue!(stderr, cursor::RestorePosition,
+               cursor::MoveToColumn(2 + self.query.chars().count() as u16))?;
+        stderr.flush()
+    }
+
+    // Clear the selector and restore the terminal
+    fn leave(&self) {
+        let mut stderr = io::stderr();
+        let _ = queue!(stderr, cursor::RestorePosition,
+                       terminal::Clear(terminal::ClearType::FromCursorDown));
+        let _ = stderr.flush();
+        let _ = terminal::disable_raw_mode();
+    }
+}
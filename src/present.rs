@@ -7,9 +7,9 @@ use crate::statics::{CONFIG};
 use prettytable::format::{LinePosition, LineSeparator, TableFormat};
 use prettytable::{Cell, Row, Table};
 use syntect::easy::HighlightLines;
-use syntect::parsing::SyntaxSet;
-use syntect::highlighting::{ThemeSet, Style};
-use syntect::util::{as_24_bit_terminal_escaped, LinesWithEndings};
+use syntect::highlighting::{Style};
+use syntect::util::{LinesWithEndings};
+use term::{Terminal};
 
 
 pub fn present(rows: &[Vec<Cell>]) {
@@ -21,35 +21,103 @@ pub fn present(rows: &[Vec<Cell>]) {
         table.add_row(Row::new(row.to_vec()));
     }
 
-    table.printstd();
+    // Only go via the terminal when colors are used, since it adds escapes
+    // even for cells without style.
+    if crate::color::enabled() {
+        table.print_tty(true);
+    } else if let Err(error) = table.print(&mut std::io::stdout()) {
+        panic!("Error when trying to print table\n{}", error);
+    }
 }
 
-pub fn present_tip(head_rows: Vec<Cell>, data: &str, data_extension: Option<String>) {
-    let tf = tableformat_inter();
-    let mut table = Table::new();
+// Options controlling how a Tip is presented
+pub struct Options {
+
+    // Write the output through the pager (see pager.rs)
+    pub paging: bool,
+
+    // Render Markdown (see markdown.rs). Some(true) when --render and
+    // Some(false) when --raw was given, otherwise None and the data style
+    // render config decides given the data extension.
+    pub render: Option<bool>,
+}
+
+impl Options {
+
+    // Associated functions
+
+    // Get the Options from the show arguments
+    pub fn from_matches(matches: &clap::ArgMatches) -> Options {
+        Options {
+            paging: !matches.is_present("no-pager"),
+            render: if matches.is_present("render") {
+                Some(true)
+            } else if matches.is_present("raw") {
+                Some(false)
+            } else {
+                None
+            },
+        }
+    }
+}
+
+// Struct holding a section of Tip data to present, either the data or a part
+pub struct Section {
+
+    // Line presented before the data, None for the Tip data
+    pub title: Option<String>,
+
+    // The data and its extension
+    pub data: String,
+    pub extension: Option<String>,
+}
+
+// Present the Tip header and its data sections.
+pub fn present_tip(head_rows: Vec<Cell>, sections: &[Section], options: &Options) {
+    let mut output = String::new();
+
+    // Add the Tip header unless env variable is set.
+    if std::env::var("TIPS_SHOW_NOHEADER").is_err() {
+        let mut table = Table::new();
+        table.set_format(tableformat_inter());
+        table.add_row(Row::new(head_rows));
+        output.push_str(&render(&table));
+    }
+
+    for section in sections {
+        if let Some(title) = &section.title {
+            if crate::color::enabled() {
+                output.push_str(&format!("\x1b[1m{}\x1b[0m\n", title));
+            } else {
+                output.push_str(title);
+                output.push('\n');
+            }
+        }
+
+        output.push_str(&present_data(&section.data, &section.extension, options));
+    }
+
+    crate::pager::page(&output, options.paging);
+}
 
-    // Print the Tip header unless env variable is set.
-    match std::env::var("TIPS_SHOW_NOHEADER") {
-        Ok(_) => (),
-        Err(_) => {
-            table.set_format(tf);
-            table.add_row(Row::new(head_rows));
-            table.printstd();
-        },
-    };
-
-    // Test if stdout is a tty. If it's not an tty we shall print the raw
-    // string to stdout, since a formatted string via syntect adds extra
+// Return the data highlighted (or rendered) given the data extension.
+fn present_data(data: &str, data_extension: &Option<String>, options: &Options) -> String {
+    let mut output = String::new();
+
+    // Test if colors shall be used (see color.rs). If not we shall print the
+    // raw string to stdout, since a formatted string via syntect adds extra
     // characters that is most probably not wanted when stdout is not a tty.
-  	if atty::is(atty::Stream::Stdout) {
+    if crate::color::enabled() {
 
-        let ps = SyntaxSet::load_defaults_newlines();
-        let ts = ThemeSet::load_defaults();
+        let ps = crate::assets::syntax_set();
+        let ts = crate::assets::theme_set();
 
-        // Get the data extention if set, owtherwise default to txt
+        // Get the data extention if set, otherwise detect it from the data
+        // and if that fails default to txt
         let de = match data_extension {
-            Some(de) => de,
-            None     => String::from("txt"),
+            Some(de) if !de.trim().is_empty() => de.to_string(),
+            _ => crate::detect::extension(data, &ps)
+                .unwrap_or_else(|| String::from("txt")),
         };
 
         // Get the syntax given the data extension, if not found default to syntax
@@ -59,18 +127,53 @@ pub fn present_tip(head_rows: Vec<Cell>, data: &str, data_extension: Option<Stri
             None         => ps.find_syntax_by_extension("txt").unwrap(),
         };
 
-        let mut h = HighlightLines::new(syntax, &ts.themes[&CONFIG.style.data.theme]);
-        for line in LinesWithEndings::from(&data) {
-            let ranges: Vec<(Style, &str)> = h.highlight(line, &ps);
-            let escaped = as_24_bit_terminal_escaped(&ranges[..], false);
-            print!("{}", escaped);
+        let theme = crate::assets::theme(&ts);
+
+        // Render Markdown if asked to, or if configured for the extension
+        let render = match options.render {
+            Some(render) => render,
+            None         => CONFIG.style.data.render.contains(&de),
+        };
+
+        if render {
+            output.push_str(&crate::markdown::render(data, &ps, theme));
+        } else {
+            let mut h = HighlightLines::new(syntax, theme);
+            for line in LinesWithEndings::from(data) {
+                let ranges: Vec<(Style, &str)> = h.highlight(line, &ps);
+                output.push_str(&crate::color::escaped(&ranges[..]));
+            }
+        }
+
+        // Reset the colors so they do not leak into the terminal or pager,
+        // and end the last line so following sections start on a new line
+        output.push_str("\x1b[0m");
+        if !data.is_empty() && !data.ends_with('\n') {
+            output.push('\n');
         }
 
     } else {
 
         // Just print the raw string, stdout is not a tty!
-        println!("{}", data);
+        output.push_str(data);
+        output.push('\n');
+    }
+
+    output
+}
+
+// Render the table to a String, keeping the cell styles when colors are
+// used.
+fn render(table: &Table) -> String {
+    if crate::color::enabled() {
+        if let Some(mut terminal) = term::terminfo::TerminfoTerminal::new(Vec::new()) {
+            if table.print_term(&mut terminal).is_ok() {
+                return String::from_utf8_lossy(&terminal.into_inner()).to_string();
+            }
+        }
     }
+
+    table.to_string()
 }
 
 fn tableformat_inter() -> TableFormat {
@@ -58,7 +58,7 @@ fn find<'a, 'b, 'c>(
 
         // Search the Tip's data component
         Component::Data => {
-            match regex.find(&tip.get_data()) {
+            match regex.find(&tip.get_all_data()) {
                 Some(_) => Some(tip),
                 None    => None,
             }
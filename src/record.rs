@@ -0,0 +1,102 @@
+// This file contains functionality to record a command and its output as a
+// Tip (record subcommand).
+//
+// The command is run via sh with stderr redirected to stdout, so the output
+// keeps the order it was written in. The output is shown while the command
+// runs and the recorded data looks like a terminal session:
+//
+//      $ ls -l /tmp
+//      <output>
+//      # exit status: 0
+
+use std::io::{Read, Write};
+use std::{io, process};
+
+
+// Data extension of recorded tips, highlighted as shell
+const EXTENSION: &str = "sh";
+
+// Entry point for record subcommand
+pub fn record(matches: &clap::ArgMatches) {
+
+    // Since arg "command" is required it is safe to call unwrap here
+    let command: Vec<&str> = matches.values_of("command").unwrap().collect();
+    let line = command_line(&command);
+
+    let (output, status) = run(&command);
+
+    let mut data = format!("$ {}\n{}", line, output);
+    if !data.ends_with('\n') {
+        data.push('\n');
+    }
+    data.push_str(&format!("# exit status: {}\n", status));
+
+    let subject = matches.value_of("subject").unwrap_or(&line);
+    let edit = !matches.is_present("no-edit");
+
+    crate::add::added(crate::add::recorded(data, subject, EXTENSION, edit));
+}
+
+// Run command, showing its output while capturing it. Returns the output and
+// the exit status.
+fn run(command: &[&str]) -> (String, i32) {
+    let mut child = match process::Command::new("sh")
+        .arg("-c")
+        .arg("exec \"$@\" 2>&1")
+        .arg("sh")
+        .args(command)
+        .stdout(process::Stdio::piped())
+        .spawn() {
+        Ok(child) => child,
+        Err(error) => {
+            panic!("Error when running command {}\n{}",
+                   command.join(" "), error)
+        },
+    };
+
+    // Copy the output to stdout as it is read
+    let mut output = Vec::new();
+    if let Some(mut pipe) = child.stdout.take() {
+        let mut stdout = io::stdout();
+        let mut buffer = [0; 4096];
+        loop {
+            let n = match pipe.read(&mut buffer) {
+                Ok(0)  => break,
+                Ok(n)  => n,
+                Err(ref error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => {
+                    panic!("Error reading output of command {}\n{}",
+                           command.join(" "), error)
+                },
+            };
+            output.extend_from_slice(&buffer[..n]);
+            let _ = stdout.write_all(&buffer[..n]).and_then(|_| stdout.flush());
+        }
+    }
+
+    let status = match child.wait() {
+        Ok(status) => status.code().unwrap_or(-1),
+        Err(error) => {
+            panic!("Error waiting for command {}\n{}",
+                   command.join(" "), error)
+        },
+    };
+
+    (String::from_utf8_lossy(&output).to_string(), status)
+}
+
+// Return the command as a shell command line, quoting arguments when needed
+fn command_line(command: &[&str]) -> String {
+    command.iter().map(|arg| quote(arg)).collect::<Vec<String>>().join(" ")
+}
+
+// Quote arg with single quotes unless it only contains safe characters
+fn quote(arg: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "-_./=:,+@%^".contains(c);
+
+    if !arg.is_empty() && arg.chars().all(safe) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
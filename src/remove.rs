@@ -10,78 +10,101 @@ struct RemoveTip {
 
     // Index of the tip in tips
     index: usize,
-
-    // uuid of the tip's data
-    data: String,
 }
 
 // Entry point for add subcommand
 pub fn remove(matches: &clap::ArgMatches) {
 
-    // Since arg "id" is required it is safe to call unwrap here
-    let id = matches.value_of("id").unwrap();
+    // Since arg "id" is required it is safe to call unwrap here. It is an id
+    // or uuid prefix.
     let mut tips = crate::tips::Tips::load();
-
-    // Remove the Tip if matched found and confirmed
-    if let Some(remove_tip) = confirm_removal(&tips, id) {
-        crate::helpers::remove_file(&remove_tip.data);
-        tips.tips.remove(remove_tip.index);
-        tips.store();
-    } else { // else exit with a message
-        println!("No tip with id: {} found", id);
-        std::process::exit(1);
+    let index = tips.find(matches.value_of("id").unwrap());
+    let id = tips.tips[index].metadata.id.unwrap_or(0);
+
+    // Without --force move the Tip to the trash, see trash.rs
+    if !matches.is_present("force") {
+        remove_index(&mut tips, index);
+        println!("Moved tip {} to trash, restore it with: tips trash restore {}", id, id);
+        return;
     }
 
+    // Remove the Tip once confirmed
+    let remove_tip = confirm_removal(&tips, index);
+    let tip = tips.tips.remove(remove_tip.index);
+    tips.store();
+    purge(&tip, &tips);
+    crate::git::commit(&crate::git::message("delete", &tip));
 }
 
-// Iterate over all tips and if one is found matching id confirm removal of
-// this Tip.
-fn confirm_removal(tips: &crate::tips::Tips, id: &str) -> Option<RemoveTip> {
-
-    // Iterate over all Tips
-    for (idx, tip) in tips.tips.iter().enumerate() {
-        if tip.metadata.id.unwrap().to_string() == id {
-
-            // print out the found Tip's summary, so user can verify the id
-            // is correct before permanently remove the Tip
-            tip.summary();
-
-            // Ask user to confirm the removal of Tip, (flush the question)
-            print!("Sure you want to delete tip ? [y/n]: ");
-            match io::stdout().flush() {
-                Ok(_) => (),
-                Err(error) => {
-                    panic!("Error when trying to flush to stdout\n{}",
-                           error)
-                },
-            };
-
-            let mut answer = String::new();
-            match io::stdin().read_line(&mut answer) {
-                Ok(_) => (),
-                Err(error) => {
-                    panic!("Error when trying to read line from stdin\n{}",
-                           error)
-                },
-            };
-
-            // Match answer, accept 'y' and 'Y' as a confirmation to remove Tip
-            // This is done by returning a RemoveTip struct with fields set to
-            // Tip.
-            match answer.trim() {
-                "y" | "Y" => {
-                    return Some(RemoveTip {
-                        index: idx,
-                        data: format!("{}/{}", &CONFIG.data, tip.data),
-                    })
-                },
-                _ => {
-                    println!("Aborted by user");
-                    std::process::exit(1);
-                }
-            }
+// Move the Tip at index in tips to the trash, and store tips
+pub fn remove_index(tips: &mut crate::tips::Tips, index: usize) {
+    let tip = tips.tips.remove(index);
+    let message = crate::git::message("remove", &tip);
+    tips.store();
+    crate::trash::put(tip);
+    crate::git::commit(&message);
+}
+
+// Remove the data, part and revision files of a Tip no longer in tips, and
+// its attachments unless used by another Tip. Files still used by a Tip in
+// tips or in the trash, such as a Tip restored by sync or import, are kept.
+pub fn purge(tip: &crate::tip::Tip, tips: &crate::tips::Tips) {
+    let trash = crate::trash::Trash::load();
+    let used = |uuid: &uuid::Uuid| {
+        tips.tips.iter()
+            .chain(trash.tips.iter().map(|trashed| &trashed.tip))
+            .any(|other| other.data == *uuid || other.parts.iter().any(|part| part.data == *uuid))
+    };
+
+    if !used(&tip.data) {
+        let data = format!("{}/{}", &CONFIG.data, tip.data);
+        crate::helpers::remove_file(&data);
+        crate::revision::remove_all(tip);
+    }
+    for part in &tip.parts {
+        if !used(&part.data) {
+            crate::helpers::remove_file(&part.path());
         }
     }
 
-    None
+    // Attachment contents may be shared with other tips
+    for attachment in &tip.attachments {
+        crate::attachment::remove_unused(attachment, tips);
+    }
+}
+
+// Confirm removal of the Tip at index in tips, exit if not confirmed
+fn confirm_removal(tips: &crate::tips::Tips, index: usize) -> RemoveTip {
+
+    // print out the found Tip's summary, so user can verify the id
+    // is correct before permanently remove the Tip
+    tips.tips[index].summary();
+
+    // Ask user to confirm the removal of Tip, (flush the question)
+    print!("Sure you want to permanently delete tip ? [y/n]: ");
+    match io::stdout().flush() {
+        Ok(_) => (),
+        Err(error) => {
+            panic!("Error when trying to flush to stdout\n{}",
+                   error)
+        },
+    };
+
+    let mut answer = String::new();
+    match io::stdin().read_line(&mut answer) {
+        Ok(_) => (),
+        Err(error) => {
+            panic!("Error when trying to read line from stdin\n{}",
+                   error)
+        },
+    };
+
+    // Match answer, accept 'y' and 'Y' as a confirmation to remove Tip
+    match answer.trim() {
+        "y" | "Y" => RemoveTip { index },
+        _ => {
+            println!("Aborted by user");
+            std::process::exit(1);
+        }
+    }
 }
@@ -0,0 +1,156 @@
+// This file contains functionality to renumber the tips (renumber
+// subcommand).
+//
+// By default only tips sharing an id with an earlier Tip are given a new id,
+// above the highest id ever given. With --compact all tips are numbered 1, 2,
+// 3 ... in id order, closing the gaps left by removed tips. A changed id no
+// Tip has any more is recorded as an alias, so the old id still refers to
+// the Tip. An old id given to another Tip by --compact refers to that Tip,
+// which is warned about. New tips are still numbered above the highest id
+// ever given, so old ids are not given to new tips.
+
+use std::collections::{BTreeSet};
+
+
+// Entry point for renumber subcommand
+pub fn renumber(matches: &clap::ArgMatches) {
+    let mut tips = crate::tips::Tips::load();
+    let compact = matches.is_present("compact");
+    let changes = if compact { compact_ids(&mut tips) } else { unique_ids(&mut tips) };
+
+    if changes.is_empty() {
+        println!("No ids changed");
+        return;
+    }
+
+    for (old, new) in &changes {
+        println!("{:>4} -> {}", old, new);
+    }
+    let reused = record(&mut tips, &changes);
+    if compact {
+        for (old, new) in reused {
+            eprintln!("Warning: id {} now refers to another tip, the tip it referred to has id {}", old, new);
+        }
+    }
+
+    tips.store();
+    crate::git::commit(&format!("renumber {} tip(s)", changes.len()));
+    println!("Renumbered {} tip(s)", changes.len());
+}
+
+// Number all tips 1, 2, 3 ... in id order, returning the changed ids as old
+// id and new id
+fn compact_ids(tips: &mut crate::tips::Tips) -> Vec<(usize, usize)> {
+    let mut changes = Vec::new();
+    let highest = tips.highest_id();
+    tips.tips.sort_by_key(|tip| (tip.metadata.id, tip.metadata.created));
+    for (position, tip) in tips.tips.iter_mut().enumerate() {
+        let old = tip.metadata.id.unwrap_or(0);
+        if old != position + 1 {
+            changes.push((old, position + 1));
+        }
+        tip.metadata.id = Some(position + 1);
+    }
+
+    // New tips keep getting ids above the highest id ever given, so the
+    // old ids kept as aliases are never reused
+    tips.last_id = highest;
+    changes
+}
+
+// Give tips sharing an id with an earlier Tip, or without an id, a new id
+// above the highest id ever given, returning the changed ids as old id and
+// new id
+fn unique_ids(tips: &mut crate::tips::Tips) -> Vec<(usize, usize)> {
+    let mut changes = Vec::new();
+    let mut next = tips.highest_id() + 1;
+    let mut seen = BTreeSet::new();
+    for tip in tips.tips.iter_mut() {
+        match tip.metadata.id {
+            Some(id) if id > 0 && seen.insert(id) => (),
+            id => {
+                changes.push((id.unwrap_or(0), next));
+                tip.metadata.id = Some(next);
+                seen.insert(next);
+                next += 1;
+            },
+        }
+    }
+    tips.tips.sort_by_key(|tip| tip.metadata.id);
+    changes
+}
+
+// Record the changed ids as aliases. Returns the old ids, with the new id of
+// their Tip, that are now the id of another Tip, so they have no alias.
+fn record(tips: &mut crate::tips::Tips, changes: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    // Aliases of earlier renumbers follow the Tip to its new id
+    let renamed: Vec<(usize, usize)> = changes.iter()
+        .filter(|(old, _)| tips.aliases.values().any(|new| new == old))
+        .cloned()
+        .collect();
+    for new in tips.aliases.values_mut() {
+        if let Some((_, renamed)) = renamed.iter().find(|(old, _)| old == new) {
+            *new = *renamed;
+        }
+    }
+
+    // A duplicated id keeps referring to the Tip that kept it
+    let mut reused = Vec::new();
+    for (old, new) in changes.iter().filter(|(old, _)| *old > 0) {
+        if tips.get_tip_index(*old).is_none() {
+            tips.aliases.insert(*old, *new);
+        } else {
+            reused.push((*old, *new));
+        }
+    }
+
+    // An alias is dropped when storing once a Tip has its old id
+    reused.extend(tips.aliases.iter()
+        .filter(|(old, _)| tips.get_tip_index(**old).is_some())
+        .map(|(old, new)| (*old, *new)));
+    reused.sort_unstable();
+    reused
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tips a to f with ids 1 to 6, with tips 2 and 4 removed
+    fn removed() -> crate::tips::Tips {
+        let tips: String = [(1, "a"), (3, "c"), (5, "e"), (6, "f")].iter()
+            .map(|(id, subject)| format!("- metadata:\n    subject: {}\n    id: {}\n  \
+                                          data: 00000000-0000-0000-0000-00000000000{}\n", subject, id, id))
+            .collect();
+        serde_yaml::from_str(&format!("last_id: 6\ntips:\n{}", tips)).unwrap()
+    }
+
+    fn subject(tips: &crate::tips::Tips, reference: &str) -> String {
+        tips.tips[tips.find(reference)].metadata.subject.to_string()
+    }
+
+    #[test]
+    fn compact_reports_reused_ids() {
+        let mut tips = removed();
+        let changes = compact_ids(&mut tips);
+        assert_eq!(changes, vec![(3, 2), (5, 3), (6, 4)]);
+        assert_eq!(record(&mut tips, &changes), vec![(3, 2)]);
+        assert_eq!(tips.last_id, 6);
+
+        assert_eq!(subject(&tips, "2"), "c");
+        assert_eq!(subject(&tips, "3"), "e");
+        assert_eq!(subject(&tips, "5"), "e");
+        assert_eq!(subject(&tips, "6"), "f");
+    }
+
+    #[test]
+    fn unique_ids_keep_aliases() {
+        let mut tips = removed();
+        tips.tips[2].metadata.id = Some(3);
+        let changes = unique_ids(&mut tips);
+        assert_eq!(changes, vec![(3, 7)]);
+        assert_eq!(record(&mut tips, &changes), vec![(3, 7)]);
+        assert_eq!(subject(&tips, "3"), "c");
+        assert_eq!(subject(&tips, "7"), "e");
+    }
+}
@@ -0,0 +1,48 @@
+// This file contains functionality to restore a Tip to one of its revisions
+// (restore subcommand). The restored version is recorded as a new revision,
+// so the restore can be undone.
+
+
+// Entry point for restore subcommand
+pub fn restore(matches: &clap::ArgMatches) {
+
+    // Since args "id" and "revision" are required it is safe to call unwrap
+    let number = crate::helpers::parse_id(matches.value_of("revision").unwrap());
+
+    let mut tips = crate::tips::Tips::load();
+    let index = tips.find(matches.value_of("id").unwrap());
+    let tip = &mut tips.tips[index];
+    let id = tip.metadata.id.unwrap_or(0);
+
+    let revisions = crate::revision::load(tip);
+    let revision = match revisions.iter().find(|r| r.revision == number) {
+        Some(revision) => revision,
+        None => {
+            eprintln!("No revision {} of tip {}", number, id);
+            std::process::exit(1);
+        },
+    };
+
+    // Keep the current version if it was never recorded
+    crate::revision::ensure(tip);
+
+    let data_updated = crate::update::update_data(tip, &revision.data);
+    let parts_updated = crate::update::update_parts(tip, revision.sections());
+    let metadata_updated = tip.metadata.subject != revision.subject ||
+        tip.metadata.tags != revision.tags ||
+        tip.metadata.data_extension != revision.data_extension;
+
+    if !(data_updated || parts_updated || metadata_updated) {
+        println!("Tip {} does not differ from revision {}", id, number);
+        return;
+    }
+
+    tip.metadata.subject = revision.subject.to_string();
+    tip.metadata.tags = revision.tags.clone();
+    tip.metadata.data_extension = revision.data_extension.clone();
+    tip.metadata.last_updated = Some(chrono::offset::Local::now());
+    crate::revision::record(tip, &format!("restored revision {}", number));
+    let message = crate::git::message("restore", tip);
+    tips.store();
+    crate::git::commit(&message);
+}
@@ -0,0 +1,207 @@
+// This file holds definition of a Tip's revisions.
+//
+// Each time a Tip is added or changed a revision is recorded, holding a
+// timestamped snapshot of the metadata and data (including parts) of the Tip.
+// Revisions are stored in the revisions directory of the tips home, one
+// directory per Tip (named by the uuid of the Tip data) and one file per
+// revision:
+//
+//      ~/.tips/revisions/<data uuid>/<revision>.yaml
+//
+// Tips added before revisions were kept get their first revision recorded
+// just before they are changed.
+
+use crate::statics::{CONFIG};
+use chrono::DateTime;
+use chrono::offset::Local;
+use serde::{Deserialize, Serialize};
+use std::{fs, path};
+
+
+// Struct holding a snapshot of a Tip
+#[derive(Serialize, Deserialize)]
+pub struct Revision {
+
+    // Revision number, starting at 1 for each Tip
+    pub revision: usize,
+
+    // When and why the revision was recorded
+    pub timestamp: DateTime<Local>,
+    pub message: String,
+
+    // The metadata fields that can be edited
+    pub subject: String,
+    pub tags: Option<Vec<String>>,
+    pub data_extension: Option<String>,
+
+    // The contents of the data and the parts
+    pub data: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<PartSnapshot>,
+}
+
+// Struct holding a snapshot of a Tip part
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PartSnapshot {
+    pub name: String,
+    pub extension: Option<String>,
+    pub data: String,
+}
+
+impl Revision {
+
+    // Functions
+
+    // Return the revision as text, used to compare revisions: the editable
+    // metadata serialized as in the update template, then the data and parts.
+    pub fn text(&self) -> String {
+        let mut metadata = serde_yaml::Mapping::new();
+        metadata.insert("subject".into(), self.subject.as_str().into());
+        metadata.insert("tags".into(), serde_yaml::to_value(&self.tags).unwrap_or_default());
+        metadata.insert("data_extension".into(), serde_yaml::to_value(&self.data_extension).unwrap_or_default());
+        let serialized = match serde_yaml::to_string(&metadata) {
+            Ok(string) => string,
+            Err(error) => {
+                panic!("Error serialize revision {}\n{}",
+                       self.revision, error)
+            },
+        };
+
+        let mut text = format!("{}\n\n{}\n",
+                               serialized.trim_start_matches("---\n").trim_end(),
+                               crate::tip::TIP.separator);
+        text.push_str(&self.data);
+
+        for part in &self.parts {
+            if !text.ends_with('\n') {
+                text.push('\n');
+            }
+            text.push_str(&crate::part::separator(&part.name, &part.extension));
+            text.push('\n');
+            text.push_str(&part.data);
+        }
+
+        text
+    }
+
+    // Return the parts as sections, as if edited in the update template
+    pub fn sections(&self) -> Vec<crate::part::Section> {
+        self.parts.iter()
+            .map(|part| crate::part::Section {
+                name: part.name.to_string(),
+                extension: part.extension.clone(),
+                data: part.data.to_string(),
+            })
+            .collect()
+    }
+
+    // Associated functions
+
+    // Create a snapshot of tip with revision number and message
+    pub fn of(tip: &crate::tip::Tip, revision: usize, message: &str) -> Revision {
+        Revision {
+            revision,
+            timestamp: Local::now(),
+            message: message.to_string(),
+            subject: tip.metadata.subject.to_string(),
+            tags: tip.metadata.tags.clone(),
+            data_extension: tip.metadata.data_extension.clone(),
+            data: tip.get_data(),
+            parts: tip.parts.iter()
+                .map(|part| PartSnapshot {
+                    name: part.name.to_string(),
+                    extension: part.extension.clone(),
+                    data: part.get_data(),
+                })
+                .collect(),
+        }
+    }
+}
+
+// Record a revision of tip with message, unless it does not differ from the
+// latest revision.
+pub fn record(tip: &crate::tip::Tip, message: &str) {
+    let revisions = load(tip);
+    let revision = Revision::of(tip, revisions.len() + 1, message);
+
+    if let Some(latest) = revisions.last() {
+        if latest.text() == revision.text() {
+            return;
+        }
+    }
+
+    store(tip, &revision);
+}
+
+// Record the first revision of tip if it has none, so the current version is
+// kept before the Tip is changed.
+pub fn ensure(tip: &crate::tip::Tip) {
+    if load(tip).is_empty() {
+        store(tip, &Revision::of(tip, 1, "first revision"));
+    }
+}
+
+// Load the revisions of tip, oldest first
+pub fn load(tip: &crate::tip::Tip) -> Vec<Revision> {
+    let dir = dir(tip);
+    if !dir.exists() {
+        return Vec::new();
+    }
+
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(error) => {
+            panic!("Error reading directory {}\n{}", dir.display(), error)
+        },
+    };
+
+    let mut revisions: Vec<Revision> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().map(|e| e == "yaml").unwrap_or(false))
+        .map(|entry| {
+            let contents = crate::helpers::read_to_string(&entry.path().to_string_lossy());
+            match serde_yaml::from_str(&contents) {
+                Ok(revision) => revision,
+                Err(error) => {
+                    panic!("Error deserialize revision {}\n{}",
+                           entry.path().display(), error)
+                },
+            }
+        })
+        .collect();
+
+    revisions.sort_by_key(|revision: &Revision| revision.revision);
+    revisions
+}
+
+// Remove all revisions of tip
+pub fn remove_all(tip: &crate::tip::Tip) {
+    let dir = dir(tip);
+    if dir.exists() {
+        if let Err(error) = fs::remove_dir_all(&dir) {
+            panic!("Error removing directory {}\n{}", dir.display(), error)
+        }
+    }
+}
+
+// Write revision of tip to file
+fn store(tip: &crate::tip::Tip, revision: &Revision) {
+    let serialized = match serde_yaml::to_string(revision) {
+        Ok(serialized) => serialized,
+        Err(error) => {
+            panic!("Error serialize revision {} of tip {}\n{}",
+                   revision.revision, tip.metadata.id.unwrap_or(0), error)
+        },
+    };
+
+    let dir = dir(tip);
+    crate::helpers::create_dir_all(&dir.to_string_lossy());
+    crate::helpers::write_to_file(
+        &dir.join(format!("{}.yaml", revision.revision)).to_string_lossy(),
+        &serialized);
+}
+
+// Return the directory holding the revisions of tip
+fn dir(tip: &crate::tip::Tip) -> path::PathBuf {
+    CONFIG.home().join("revisions").join(tip.data.to_string())
+}
@@ -0,0 +1,238 @@
+// This file contains functionality to run a Tip as a shell command (run
+// subcommand).
+//
+// The data of the Tip may contain placeholders, which are prompted for before
+// the command is run:
+//      <name>, a value without default
+//      {{name}}, same as <name>
+//      {{name:default}}, a value with a default
+//
+// Values entered are remembered in run_history.yaml in the tips home and
+// offered as suggestions the next time a placeholder with the same name is
+// prompted for.
+
+use crate::statics::{CONFIG};
+use regex::{Captures, Regex};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Write};
+use std::{env, io, process};
+
+
+// Maximum number of remembered values per placeholder
+const MAX_HISTORY: usize = 10;
+
+// Shell used when $SHELL is not set
+const DEFAULT_SHELL: &str = "sh";
+
+lazy_static::lazy_static! {
+    static ref PLACEHOLDER: Regex = Regex::new(
+        r"\{\{\s*([A-Za-z_][A-Za-z0-9_-]*)\s*(?::([^}]*))?\}\}|<([A-Za-z_][A-Za-z0-9_-]*)>"
+    ).unwrap();
+}
+
+// Struct representing a placeholder in the data of a Tip
+struct Placeholder {
+
+    // Name of the placeholder, the same name is only prompted for once
+    name: String,
+
+    // Default value from {{name:default}}
+    default: Option<String>,
+}
+
+// Entry point for run subcommand
+pub fn run(matches: &clap::ArgMatches) {
+
+    // Since arg "id" is required it is safe to call unwrap here
+    let tips = crate::tips::Tips::load();
+    let tip = &tips.tips[tips.find(matches.value_of("id").unwrap())];
+
+    let data = tip.get_data();
+    let mut history = load_history();
+
+    // Prompt for a value for each placeholder
+    let mut values: HashMap<String, String> = HashMap::new();
+    for placeholder in placeholders(&data) {
+        if values.contains_key(&placeholder.name) {
+            continue;
+        }
+
+        let previous = history.get(&placeholder.name).cloned().unwrap_or_default();
+        let value = prompt_value(&placeholder, &previous);
+        values.insert(placeholder.name, value);
+    }
+
+    let command = substitute(&data, &values);
+
+    // Show the final command and ask for confirmation
+    eprintln!("{}", command.trim_end());
+    if !matches.is_present("yes") && !confirm() {
+        eprintln!("Aborted by user");
+        process::exit(1);
+    }
+
+    // Remember the values, most recent first
+    for (name, value) in values {
+        let previous = history.entry(name).or_default();
+        previous.retain(|v| v != &value);
+        previous.insert(0, value);
+        previous.truncate(MAX_HISTORY);
+    }
+    store_history(&history);
+
+    process::exit(execute(&command));
+}
+
+// Return the placeholders in data, in order of appearance
+fn placeholders(data: &str) -> Vec<Placeholder> {
+    PLACEHOLDER.captures_iter(data)
+        .map(|caps| Placeholder {
+            name: name(&caps).to_string(),
+            default: caps.get(2).map(|m| m.as_str().to_string()),
+        })
+        .collect()
+}
+
+// Return the name of a matched placeholder, from either syntax
+fn name<'a>(caps: &Captures<'a>) -> &'a str {
+    match caps.get(1).or_else(|| caps.get(3)) {
+        Some(m) => m.as_str(),
+        None    => "",
+    }
+}
+
+// Replace the placeholders in data with values
+fn substitute(data: &str, values: &HashMap<String, String>) -> String {
+    PLACEHOLDER.replace_all(data, |caps: &Captures| {
+        values.get(name(caps)).cloned().unwrap_or_default()
+    }).to_string()
+}
+
+// Prompt for the value of placeholder on stderr. The default is the default
+// of the placeholder or else the last value used, and previous values are
+// listed as suggestions. A value is required when there is no default.
+fn prompt_value(placeholder: &Placeholder, previous: &[String]) -> String {
+    let default = placeholder.default.clone()
+        .or_else(|| previous.first().cloned());
+
+    let suggestions: Vec<&String> = previous.iter()
+        .filter(|value| Some(*value) != default.as_ref())
+        .collect();
+
+    loop {
+        let mut prompt = placeholder.name.to_string();
+        if let Some(default) = &default {
+            prompt.push_str(&format!(" [{}]", default));
+        }
+        if !suggestions.is_empty() {
+            let list: Vec<&str> = suggestions.iter().map(|s| s.as_str()).collect();
+            prompt.push_str(&format!(" (previous: {})", list.join(", ")));
+        }
+
+        let answer = read_answer(&format!("{}: ", prompt));
+        match (answer.is_empty(), &default) {
+            (false, _)             => return answer,
+            (true, Some(default))  => return default.to_string(),
+            (true, None)           => continue,
+        }
+    }
+}
+
+// Ask the user to confirm running the command
+fn confirm() -> bool {
+    matches!(read_answer("Run command ? [y/n]: ").as_str(), "y" | "Y")
+}
+
+// Print question on stderr and read a line from stdin. Exits if stdin is
+// closed.
+fn read_answer(question: &str) -> String {
+    eprint!("{}", question);
+    match io::stderr().flush() {
+        Ok(_) => (),
+        Err(error) => {
+            panic!("Error when trying to flush to stderr\n{}", error)
+        },
+    };
+
+    let mut answer = String::new();
+    match io::stdin().read_line(&mut answer) {
+        Ok(0) => {
+            eprintln!();
+            eprintln!("Aborted by user");
+            process::exit(1);
+        },
+        Ok(_) => (),
+        Err(error) => {
+            panic!("Error when trying to read line from stdin\n{}", error)
+        },
+    };
+
+    answer.trim_end_matches(&['\r', '\n'][..]).to_string()
+}
+
+// Execute command in $SHELL and return its exit code
+fn execute(command: &str) -> i32 {
+    let shell = env::var("SHELL").unwrap_or_else(|_| DEFAULT_SHELL.to_string());
+
+    let status = match process::Command::new(&shell)
+        .arg("-c")
+        .arg(command)
+        .status() {
+        Ok(status) => status,
+        Err(error) => {
+            panic!("Error when running command with {}\n{}", shell, error)
+        },
+    };
+
+    // A command killed by a signal has no code, use the shell convention
+    match status.code() {
+        Some(code) => code,
+        None       => 128 + signal(&status),
+    }
+}
+
+// Return the signal that terminated the command
+#[cfg(unix)]
+fn signal(status: &process::ExitStatus) -> i32 {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal().unwrap_or(0)
+}
+
+#[cfg(not(unix))]
+fn signal(_status: &process::ExitStatus) -> i32 {
+    0
+}
+
+// Path of the file holding previous placeholder values
+fn history_file() -> std::path::PathBuf {
+    CONFIG.home().join("run_history.yaml")
+}
+
+// Load previous placeholder values, empty if none are stored yet
+fn load_history() -> BTreeMap<String, Vec<String>> {
+    let path = history_file();
+    if !path.exists() {
+        return BTreeMap::new();
+    }
+
+    let data = crate::helpers::read_to_string(&path.to_string_lossy());
+    match serde_yaml::from_str(&data) {
+        Ok(history) => history,
+        Err(error) => {
+            panic!("Error parsing run history {}\n{}",
+                   path.display(), error)
+        },
+    }
+}
+
+// Store previous placeholder values
+fn store_history(history: &BTreeMap<String, Vec<String>>) {
+    let data = match serde_yaml::to_string(history) {
+        Ok(data) => data,
+        Err(error) => {
+            panic!("Error serializing run history\n{}", error)
+        },
+    };
+
+    crate::helpers::write_to_file(&history_file().to_string_lossy(), &data);
+}
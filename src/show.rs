@@ -1,31 +1,27 @@
 // This file contains functionality to show a Tip
 
-use std::str::{FromStr};
+use crate::format::{Format};
 
 
 // Entry point for add subcommand
 pub fn show(matches: &clap::ArgMatches) {
 
-    // Since arg "id" is required it is safe to call unwrap here
-    let id_str = matches.value_of("id").unwrap();
-
-    // Convert id to usize
-    let id: usize = match usize::from_str(id_str) {
-        Ok(id) => id,
-        Err(error) => {
-            panic!("Error converting str id {} to usize\n{}",
-                   id_str, error)
-        },
+    // Since arg "id" is required it is safe to call unwrap here. The id (or
+    // uuid prefix) may be followed by a part name, as in 12:manifest
+    let arg = matches.value_of("id").unwrap();
+    let (reference, part) = match arg.split_once(':') {
+        Some((reference, part)) => (reference, Some(part)),
+        None                    => (arg, None),
     };
 
-    // Load tips
+    // Load tips and present the tip referred to
     let tips = crate::tips::Tips::load();
-
-    // If we get an index for the tip present it. Otherwise panic.
-    if let Some(index) = tips.get_tip_index(id) {
-        let tip = &tips.tips[index];
-        tip.present();
-    } else {
-        panic!("No tip in DB for ID: {}", id)
+    let tip = &tips.tips[tips.find(reference)];
+    let options = crate::present::Options::from_matches(matches);
+    match (Format::from_matches(matches), part) {
+        (Format::Table, Some(part)) => tip.present_part(part, &options),
+        (Format::Table, None)       => tip.present(&options),
+        (format, _) => crate::format::output(
+            &[tip], &format, !matches.is_present("no-content")),
     }
 }
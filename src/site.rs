@@ -0,0 +1,398 @@
+// This file contains functionality to render the pages of the static HTML
+// site written by "tips export html":
+//
+//      index.html          all tips and all tags
+//      tags/<tag>.html     the tips with the tag
+//      tips/<tip>.html     the data, parts and attachments of a Tip
+//      search-index.js     the subjects, tags and data of the tips as JSON
+//      search.js           the search, run in the browser
+//      style.css
+//
+// Data is highlighted by syntect as HTML with inline colors using the
+// configured theme. Markdown data is rendered with the fenced code blocks
+// highlighted the same way. The search index is a script rather than a JSON
+// file, so the site also works when opened from disk.
+
+use chrono::DateTime;
+use chrono::offset::Local;
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag};
+use serde::{Serialize};
+use std::collections::{BTreeMap};
+use syntect::highlighting::{Theme};
+use syntect::parsing::SyntaxSet;
+
+
+// Style sheet of the site
+pub const STYLE: &str = r#"body {
+    font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif;
+    max-width: 60em;
+    margin: 0 auto;
+    padding: 0 1em 2em;
+    color: #222;
+}
+header {
+    display: flex;
+    flex-wrap: wrap;
+    align-items: center;
+    gap: 1em;
+    padding: 1em 0;
+    border-bottom: 1px solid #ddd;
+}
+header > a {
+    font-weight: bold;
+    font-size: 1.2em;
+    color: inherit;
+    text-decoration: none;
+}
+#search {
+    flex: 1;
+    padding: 0.3em;
+    font-size: 1em;
+}
+#results {
+    width: 100%;
+    margin: 0;
+}
+#results:empty {
+    display: none;
+}
+pre {
+    padding: 0.8em;
+    overflow-x: auto;
+    border-radius: 4px;
+}
+table {
+    border-collapse: collapse;
+}
+td, th {
+    text-align: left;
+    padding: 0.2em 1em 0.2em 0;
+    vertical-align: top;
+}
+.id, .meta {
+    color: #777;
+}
+.tag {
+    margin-right: 0.5em;
+}
+"#;
+
+// Search run in the browser, showing the tips holding every word typed
+pub const SEARCH: &str = r#"(function () {
+    var input = document.getElementById("search");
+    var results = document.getElementById("results");
+    var root = document.body.getAttribute("data-root");
+    input.addEventListener("input", function () {
+        var words = input.value.toLowerCase().split(/\s+/).filter(function (word) {
+            return word.length > 0;
+        });
+        results.innerHTML = "";
+        if (words.length === 0) {
+            return;
+        }
+        TIPS.filter(function (tip) {
+            var text = (tip.subject + " " + tip.tags.join(" ") + " " + tip.text).toLowerCase();
+            return words.every(function (word) {
+                return text.indexOf(word) >= 0;
+            });
+        }).slice(0, 50).forEach(function (tip) {
+            var item = document.createElement("li");
+            var link = document.createElement("a");
+            link.href = root + tip.url;
+            link.textContent = tip.id + " " + tip.subject;
+            item.appendChild(link);
+            results.appendChild(item);
+        });
+    });
+})();
+"#;
+
+// Struct holding a Tip in the search index
+#[derive(Serialize)]
+struct SearchEntry {
+    id: usize,
+    subject: String,
+    tags: Vec<String>,
+    url: String,
+    text: String,
+}
+
+// Struct holding the tips of the site and the names of their pages
+pub struct Site<'a> {
+
+    // The tips, sorted by id
+    tips: &'a [crate::tip::Tip],
+
+    // File name of the page of each Tip
+    pages: Vec<String>,
+
+    // File name of the page of each tag, and the positions of its tips
+    tags: BTreeMap<String, (String, Vec<usize>)>,
+
+    // Syntax set and theme used to highlight data
+    ps: SyntaxSet,
+    theme: Theme,
+}
+
+impl<'a> Site<'a> {
+
+    // Functions
+
+    // Return the index page
+    pub fn index(&self) -> String {
+        let all: Vec<usize> = (0..self.tips.len()).collect();
+        let mut body = format!("<h1>Tips</h1>\n{}", self.table(&all, ""));
+
+        if !self.tags.is_empty() {
+            body.push_str("<h2>Tags</h2>\n<ul>\n");
+            for (tag, (page, positions)) in &self.tags {
+                body.push_str(&format!("<li><a href=\"tags/{}\">{}</a> ({})</li>\n",
+                                       page, escape(tag), positions.len()));
+            }
+            body.push_str("</ul>\n");
+        }
+
+        page("Tips", "", &body)
+    }
+
+    // Return the page of tag
+    pub fn tag_page(&self, tag: &str) -> String {
+        let positions = self.tags.get(tag).map(|(_, positions)| positions.as_slice()).unwrap_or_default();
+        let body = format!("<h1>Tag: {}</h1>\n{}", escape(tag), self.table(positions, "../"));
+        page(tag, "../", &body)
+    }
+
+    // Return the page of the Tip at position
+    pub fn tip_page(&self, position: usize) -> String {
+        let tip = &self.tips[position];
+        let meta: Vec<String> = vec![
+            format!("Id {}", tip.metadata.id.unwrap_or(0)),
+            self.tag_links(tip, "../"),
+            date("Created ", &tip.metadata.created),
+            date("Updated ", &tip.metadata.last_updated),
+        ];
+        let meta: Vec<String> = meta.into_iter().filter(|item| !item.is_empty()).collect();
+        let mut body = format!("<h1>{}</h1>\n<p class=\"meta\">{}</p>\n",
+                               escape(&tip.metadata.subject),
+                               meta.join(" &middot; "));
+
+        body.push_str(&self.data(&tip.get_data(), &tip.metadata.data_extension));
+        for part in &tip.parts {
+            body.push_str(&format!("<h2>{}</h2>\n", escape(&part.name)));
+            body.push_str(&self.data(&part.get_data(), &part.extension));
+        }
+
+        if !tip.attachments.is_empty() {
+            body.push_str("<h2>Attachments</h2>\n<ul>\n");
+            for attachment in &tip.attachments {
+                let href: Vec<String> = attachment_path(attachment).split('/').map(percent_encode).collect();
+                body.push_str(&format!("<li><a href=\"../{}\">{}</a> ({}, {})</li>\n",
+                                       href.join("/"),
+                                       escape(&attachment.name),
+                                       crate::columns::human_size(attachment.size),
+                                       escape(&attachment.mime)));
+            }
+            body.push_str("</ul>\n");
+        }
+
+        page(&tip.metadata.subject, "../", &body)
+    }
+
+    // Return the search index script, defining TIPS
+    pub fn search_index(&self) -> String {
+        let entries: Vec<SearchEntry> = self.tips.iter().zip(self.pages.iter())
+            .map(|(tip, page)| SearchEntry {
+                id: tip.metadata.id.unwrap_or(0),
+                subject: tip.metadata.subject.to_string(),
+                tags: tip.metadata.tags.clone().unwrap_or_default(),
+                url: format!("tips/{}", page),
+                text: crate::part::join(&tip.get_data(), &tip.parts),
+            })
+            .collect();
+
+        match serde_json::to_string(&entries) {
+            Ok(json) => format!("var TIPS = {};\n", json),
+            Err(error) => panic!("Error serialize search index\n{}", error),
+        }
+    }
+
+    // Return the table of the tips at positions, with links relative to root
+    fn table(&self, positions: &[usize], root: &str) -> String {
+        let mut table = String::from("<table>\n");
+        for position in positions {
+            let tip = &self.tips[*position];
+            table.push_str(&format!("<tr><td class=\"id\">{}</td><td><a href=\"{}tips/{}\">{}</a></td><td>{}</td></tr>\n",
+                                    tip.metadata.id.unwrap_or(0),
+                                    root,
+                                    self.pages[*position],
+                                    escape(&tip.metadata.subject),
+                                    self.tag_links(tip, root)));
+        }
+        table.push_str("</table>\n");
+        table
+    }
+
+    // Return links to the tag pages of tip, relative to root
+    fn tag_links(&self, tip: &crate::tip::Tip, root: &str) -> String {
+        tip.metadata.tags.clone().unwrap_or_default().iter()
+            .filter_map(|tag| self.tags.get(tag).map(|(page, _)| {
+                format!("<a class=\"tag\" href=\"{}tags/{}\">{}</a>", root, page, escape(tag))
+            }))
+            .collect()
+    }
+
+    // Return data as HTML: rendered if Markdown, otherwise highlighted
+    fn data(&self, data: &str, extension: &Option<String>) -> String {
+        let extension = match extension {
+            Some(extension) if !extension.trim().is_empty() => extension.to_string(),
+            _ => crate::detect::extension(data, &self.ps).unwrap_or_else(|| String::from("txt")),
+        };
+
+        if extension == "md" || extension == "markdown" {
+            return self.markdown(data);
+        }
+
+        let syntax = match self.ps.find_syntax_by_extension(&extension) {
+            Some(syntax) => syntax,
+            None         => self.ps.find_syntax_plain_text(),
+        };
+        syntect::html::highlighted_html_for_string(data, &self.ps, syntax, &self.theme)
+    }
+
+    // Return Markdown data as HTML with the fenced code blocks highlighted
+    fn markdown(&self, data: &str) -> String {
+        let mut options = Options::empty();
+        options.insert(Options::ENABLE_TABLES);
+        options.insert(Options::ENABLE_STRIKETHROUGH);
+        options.insert(Options::ENABLE_TASKLISTS);
+
+        // Language and contents of the code block being rendered
+        let mut code: Option<(String, String)> = None;
+        let mut events = Vec::new();
+        for event in Parser::new_ext(data, options) {
+            match event {
+                Event::Start(Tag::CodeBlock(kind)) => {
+                    let lang = match kind {
+                        CodeBlockKind::Fenced(lang) => lang.to_string(),
+                        CodeBlockKind::Indented     => String::new(),
+                    };
+                    code = Some((lang, String::new()));
+                },
+                Event::End(Tag::CodeBlock(_)) => {
+                    if let Some((lang, contents)) = code.take() {
+                        let syntax = match self.ps.find_syntax_by_token(&lang) {
+                            Some(syntax) if !lang.is_empty() => syntax,
+                            _ => self.ps.find_syntax_plain_text(),
+                        };
+                        let html = syntect::html::highlighted_html_for_string(&contents, &self.ps, syntax, &self.theme);
+                        events.push(Event::Html(html.into()));
+                    }
+                },
+                Event::Text(text) => match code.as_mut() {
+                    Some((_, contents)) => contents.push_str(&text),
+                    None                => events.push(Event::Text(text)),
+                },
+                event => events.push(event),
+            }
+        }
+
+        let mut html = String::new();
+        pulldown_cmark::html::push_html(&mut html, events.into_iter());
+        html
+    }
+
+    // Associated functions
+
+    // Create the site of tips, sorted by id, with the file names of their
+    // pages and of the pages of the tags
+    pub fn new(tips: &'a [crate::tip::Tip], pages: Vec<String>, tag_pages: BTreeMap<String, String>) -> Site<'a> {
+        let mut tags: BTreeMap<String, (String, Vec<usize>)> = tag_pages.into_iter()
+            .map(|(tag, page)| (tag, (page, Vec::new())))
+            .collect();
+        for (position, tip) in tips.iter().enumerate() {
+            for tag in tip.metadata.tags.clone().unwrap_or_default() {
+                if let Some((_, positions)) = tags.get_mut(&tag) {
+                    positions.push(position);
+                }
+            }
+        }
+
+        let ts = crate::assets::theme_set();
+        Site {
+            tips,
+            pages,
+            tags,
+            ps: crate::assets::syntax_set(),
+            theme: crate::assets::theme(&ts).clone(),
+        }
+    }
+}
+
+// Return the path, relative to the site, of the copy of attachment
+pub fn attachment_path(attachment: &crate::attachment::Attachment) -> String {
+    let name = std::path::Path::new(&attachment.name).file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| attachment.sha256.to_string());
+    format!("attachments/{}/{}", attachment.sha256, name)
+}
+
+// Return a page with title and body. The links of the header are relative
+// to root, the path from the page to the site.
+fn page(title: &str, root: &str, body: &str) -> String {
+    format!(r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<link rel="stylesheet" href="{root}style.css">
+</head>
+<body data-root="{root}">
+<header>
+<a href="{root}index.html">Tips</a>
+<input id="search" type="search" placeholder="Search" autocomplete="off">
+<ul id="results"></ul>
+</header>
+<main>
+{body}</main>
+<script src="{root}search-index.js"></script>
+<script src="{root}search.js"></script>
+</body>
+</html>
+"#, title = escape(title), root = root, body = body)
+}
+
+// Return the date prefixed by label, or nothing if not set
+fn date(label: &str, date: &Option<DateTime<Local>>) -> String {
+    match date {
+        Some(date) => format!("{}{}", label, date.format("%Y-%m-%d %H:%M")),
+        None       => String::new(),
+    }
+}
+
+// Return text with the characters special to HTML escaped
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&'  => escaped.push_str("&amp;"),
+            '<'  => escaped.push_str("&lt;"),
+            '>'  => escaped.push_str("&gt;"),
+            '"'  => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c    => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Return a segment of a URL path with every byte but the unreserved
+// characters percent-encoded
+fn percent_encode(segment: &str) -> String {
+    segment.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            b => format!("%{:02X}", b),
+        })
+        .collect()
+}
@@ -0,0 +1,486 @@
+// This file contains functionality to sync the tips with a remote (sync
+// subcommand), so the same tips can be used on several machines.
+//
+// The remote is either a git repository (bare, or given by URL) or a plain
+// directory. It holds tips.yaml, with the metadata and contents of every Tip,
+// and the attachment contents in the attachments directory. A git remote is
+// cloned to a temporary directory, merged into and pushed back, and only
+// once pushed are the local tips changed.
+//
+// Tips are identified by the uuid of their data, since ids are assigned
+// locally. The merge is three-way: sync_base.yaml in the tips home keeps a
+// hash of every Tip as of the last sync with the remote, so a Tip changed on
+// one side takes that change and a Tip removed on one side is removed on the
+// other (locally it is moved to the trash). A Tip changed on both sides is
+// a conflict: the local version is kept and the remote version is added as
+// a new Tip tagged conflict, to be resolved by hand. Tips from the remote
+// whose id is taken get the next free id.
+
+use crate::statics::{CONFIG};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::{fs, path, process};
+
+
+// Name of the file in the remote holding the tips
+const TIPS_FILE: &str = "tips.yaml";
+
+// Tag of the tips holding the remote version of a conflict
+const CONFLICT_TAG: &str = "conflict";
+
+// Struct holding the tips of the remote
+#[derive(Serialize, Deserialize, Default)]
+struct Remote {
+    tips: Vec<SyncedTip>,
+}
+
+// Struct holding a Tip as stored in the remote, with its contents
+#[derive(Serialize, Deserialize, Clone)]
+struct SyncedTip {
+    uuid: uuid::Uuid,
+    metadata: crate::metadata::Metadata,
+    data: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    parts: Vec<crate::revision::PartSnapshot>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    attachments: Vec<crate::attachment::Attachment>,
+}
+
+// Struct holding the hash of every Tip as of the last sync with remote
+#[derive(Serialize, Deserialize, Default)]
+struct Base {
+    remote: String,
+    tips: BTreeMap<uuid::Uuid, String>,
+}
+
+// What to do with a Tip to merge the local and remote tips
+enum Action {
+
+    // Keep the local Tip at index as is
+    Keep(usize),
+
+    // Replace the local Tip at index with the remote version
+    Update(usize, SyncedTip),
+
+    // Add a Tip from the remote
+    Add(SyncedTip),
+
+    // Add the remote version of a Tip changed on both sides
+    Conflict(SyncedTip),
+
+    // Move the local Tip at index to the trash
+    Trash(usize),
+}
+
+impl SyncedTip {
+
+    // Functions
+
+    // Return a hash of the contents, leaving out the id and timestamps since
+    // they differ between machines.
+    fn hash(&self) -> String {
+        let mut contents = format!("{}\n{:?}\n{:?}\n{}\n",
+                                   self.metadata.subject,
+                                   self.metadata.tags,
+                                   self.metadata.data_extension,
+                                   self.data);
+        for part in &self.parts {
+            contents.push_str(&format!("{}\n{:?}\n{}\n", part.name, part.extension, part.data));
+        }
+        for attachment in &self.attachments {
+            contents.push_str(&format!("{}\n{}\n", attachment.name, attachment.sha256));
+        }
+
+        format!("{:x}", Sha256::digest(contents.as_bytes()))
+    }
+
+    // Return the parts as sections, as if edited in the update template
+    fn sections(&self) -> Vec<crate::part::Section> {
+        self.parts.iter()
+            .map(|part| crate::part::Section {
+                name: part.name.to_string(),
+                extension: part.extension.clone(),
+                data: part.data.to_string(),
+            })
+            .collect()
+    }
+
+    // Associated functions
+
+    // Create a SyncedTip of tip
+    fn of(tip: &crate::tip::Tip) -> SyncedTip {
+        SyncedTip {
+            uuid: tip.data,
+            metadata: tip.metadata.clone(),
+            data: tip.get_data(),
+            parts: crate::revision::Revision::of(tip, 0, "").parts,
+            attachments: tip.attachments.clone(),
+        }
+    }
+}
+
+// Entry point for sync subcommand
+pub fn sync(matches: &clap::ArgMatches) {
+    let remote = match matches.value_of("remote").map(String::from).or_else(|| CONFIG.sync.clone()) {
+        Some(remote) => remote,
+        None => {
+            eprintln!("No remote to sync with, set sync in ~/.tipsrc or give it as argument");
+            process::exit(1);
+        },
+    };
+
+    if !is_git(&remote) {
+        crate::helpers::create_dir_all(&remote);
+        let actions = merge(&remote, path::Path::new(&remote));
+        apply(&remote, path::Path::new(&remote), actions);
+        return;
+    }
+
+    // Work in a clone of the git remote, which is removed when done
+    let temp = std::env::temp_dir();
+    let clone = temp.join(format!("tips-sync-{}", uuid::Uuid::new_v4()));
+    crate::git::run(&temp, &["clone", "--quiet", &remote, &clone.to_string_lossy()]);
+
+    let actions = merge(&remote, &clone);
+    crate::git::run(&clone, &["add", "--all"]);
+    if crate::git::status(&clone, &["diff", "--cached", "--quiet"]) != 0 {
+        crate::git::run(&clone, &["commit", "--quiet", "--message", "sync tips"]);
+        if crate::git::status(&clone, &["push", "--quiet", "origin", "HEAD"]) != 0 {
+            remove_clone(&clone);
+            eprintln!("Unable to push to {}, nothing was changed. Run sync again.", remote);
+            process::exit(1);
+        }
+    }
+
+    apply(&remote, &clone, actions);
+    remove_clone(&clone);
+}
+
+// Merge the local tips with the tips in the remote directory dir, writing the
+// merged tips to dir. Return the actions needed to merge the local tips.
+fn merge(remote: &str, dir: &path::Path) -> Vec<Action> {
+    let tips = crate::tips::Tips::load();
+    let remote_tips = load_remote(dir);
+
+    // The base only applies to the remote it was recorded for
+    let base = load_base();
+    let base = if base.remote == remote { base.tips } else { BTreeMap::new() };
+
+    let mut actions = Vec::new();
+    let mut local_uuids = BTreeSet::new();
+    for (index, tip) in tips.tips.iter().enumerate() {
+        local_uuids.insert(tip.data);
+        let local_hash = SyncedTip::of(tip).hash();
+        let base_hash = base.get(&tip.data);
+
+        match remote_tips.tips.iter().find(|synced| synced.uuid == tip.data) {
+            Some(synced) => {
+                let remote_hash = synced.hash();
+                if remote_hash == local_hash || base_hash == Some(&remote_hash) {
+                    actions.push(Action::Keep(index));
+                } else if base_hash == Some(&local_hash) {
+                    actions.push(Action::Update(index, synced.clone()));
+                } else {
+                    actions.push(Action::Keep(index));
+                    actions.push(Action::Conflict(conflict(synced)));
+                }
+            },
+
+            // Removed from the remote, unless changed locally since
+            None => match base_hash {
+                Some(base_hash) if *base_hash == local_hash => actions.push(Action::Trash(index)),
+                _                                           => actions.push(Action::Keep(index)),
+            },
+        }
+    }
+
+    // Tips only in the remote are new, unless removed locally and not
+    // changed in the remote since
+    for synced in remote_tips.tips {
+        if local_uuids.contains(&synced.uuid) {
+            continue;
+        }
+        if base.get(&synced.uuid) != Some(&synced.hash()) {
+            actions.push(Action::Add(synced));
+        }
+    }
+
+    assign_ids(&tips, &mut actions);
+
+    // The merged tips make up the remote
+    let merged = Remote {
+        tips: actions.iter()
+            .filter_map(|action| match action {
+                Action::Keep(index)      => Some(SyncedTip::of(&tips.tips[*index])),
+                Action::Update(_, synced) |
+                Action::Add(synced)      |
+                Action::Conflict(synced) => Some(synced.clone()),
+                Action::Trash(_)         => None,
+            })
+            .collect(),
+    };
+    store_remote(dir, &merged, &tips);
+
+    actions
+}
+
+// Give the tips added from the remote their remote id if free, or else the
+// next id above the highest id ever given.
+fn assign_ids(tips: &crate::tips::Tips, actions: &mut [Action]) {
+    let mut used: BTreeSet<usize> = actions.iter()
+        .filter_map(|action| match action {
+            Action::Keep(index) | Action::Update(index, _) => tips.tips[*index].metadata.id,
+            _                                              => None,
+        })
+        .collect();
+    let mut next = tips.highest_id() + 1;
+
+    for action in actions.iter_mut() {
+        let (synced, conflict) = match action {
+            Action::Add(synced)      => (synced, false),
+            Action::Conflict(synced) => (synced, true),
+            _                        => continue,
+        };
+
+        let id = synced.metadata.id.filter(|id| !used.contains(id));
+        let id = id.unwrap_or_else(|| {
+            next = next.max(used.iter().next_back().map(|last| last + 1).unwrap_or(1));
+            next
+        });
+        if synced.metadata.id != Some(id) && !conflict {
+            println!("Id {} is taken, added tip \"{}\" as tip {}",
+                     synced.metadata.id.unwrap_or(0), synced.metadata.subject, id);
+        }
+        synced.metadata.id = Some(id);
+        used.insert(id);
+    }
+}
+
+// Apply actions to the local tips, reading the contents of attachments from
+// the remote directory dir, and record the merged tips as the new base.
+fn apply(remote: &str, dir: &path::Path, actions: Vec<Action>) {
+    let mut tips = crate::tips::Tips::load();
+    let mut trash = crate::trash::Trash::load();
+    let mut trashed = Vec::new();
+    let mut replaced = Vec::new();
+    let mut untrashed = Vec::new();
+    let (mut added, mut updated, mut conflicts) = (0, 0, 0);
+    let mut base = Base { remote: remote.to_string(), tips: BTreeMap::new() };
+
+    for action in actions {
+        match action {
+            Action::Keep(index) => {
+                let tip = &tips.tips[index];
+                base.tips.insert(tip.data, SyncedTip::of(tip).hash());
+            },
+            Action::Update(index, synced) => {
+                let tip = &mut tips.tips[index];
+                crate::revision::ensure(tip);
+                crate::update::update_data(tip, &synced.data);
+                crate::update::update_parts(tip, synced.sections());
+                tip.metadata.subject = synced.metadata.subject.to_string();
+                tip.metadata.tags = synced.metadata.tags.clone();
+                tip.metadata.data_extension = synced.metadata.data_extension.clone();
+                tip.metadata.last_updated = synced.metadata.last_updated;
+                replaced.append(&mut std::mem::replace(&mut tip.attachments, store_attachments(dir, &synced)));
+                crate::revision::record(tip, "synced");
+                base.tips.insert(synced.uuid, synced.hash());
+                updated += 1;
+            },
+            Action::Add(synced) => {
+
+                // A Tip removed locally but changed in the remote is added
+                // back, under the same uuid as the copy in the trash
+                let (same, others) = std::mem::take(&mut trash.tips).into_iter()
+                    .partition(|trashed| trashed.tip.data == synced.uuid);
+                trash.tips = others;
+                untrashed.extend(same);
+
+                added += 1;
+                base.tips.insert(synced.uuid, synced.hash());
+                tips.tips.push(create(dir, &synced));
+            },
+            Action::Conflict(synced) => {
+                let tip = create(dir, &synced);
+                println!("Conflict: a tip was changed on both sides, the remote version is tip {}: {}",
+                         tip.metadata.id.unwrap_or(0), tip.metadata.subject);
+                conflicts += 1;
+                base.tips.insert(synced.uuid, synced.hash());
+                tips.tips.push(tip);
+            },
+            Action::Trash(index) => trashed.push(index),
+        }
+    }
+
+    // Remove from the back, so the remaining indexes stay valid
+    let mut removed = Vec::new();
+    for index in trashed.into_iter().rev() {
+        removed.push(tips.tips.remove(index));
+    }
+
+    // Keep the tips ordered by id
+    tips.tips.sort_by_key(|tip| tip.metadata.id);
+    tips.store();
+
+    // Drop the trash copies of the tips added back, removing only the files
+    // the added tips no longer use
+    if !untrashed.is_empty() {
+        trash.store();
+        for trashed in &untrashed {
+            crate::remove::purge(&trashed.tip, &tips);
+        }
+    }
+
+    let trashed = removed.len();
+    for tip in removed {
+        crate::trash::put(tip);
+    }
+    for attachment in &replaced {
+        crate::attachment::remove_unused(attachment, &tips);
+    }
+    store_base(&base);
+
+    crate::git::commit(&format!("sync tips with {}", remote));
+    println!("Synced with {}: {} added, {} updated, {} removed, {} conflict(s)",
+             remote, added, updated, trashed, conflicts);
+}
+
+// Create a Tip of synced, writing its data and part files and storing its
+// attachments from the remote directory dir.
+fn create(dir: &path::Path, synced: &SyncedTip) -> crate::tip::Tip {
+    crate::helpers::write_to_file(&format!("{}/{}", &CONFIG.data, synced.uuid), &synced.data);
+    let tip = crate::tip::Tip {
+        metadata: synced.metadata.clone(),
+        data: synced.uuid,
+        parts: crate::part::create(synced.sections()),
+        attachments: store_attachments(dir, synced),
+    };
+    crate::revision::record(&tip, "synced");
+    tip
+}
+
+// Return a copy of synced with a new uuid, tagged as conflict
+fn conflict(synced: &SyncedTip) -> SyncedTip {
+    let mut conflict = synced.clone();
+    conflict.uuid = uuid::Uuid::new_v4();
+    conflict.metadata.subject = format!("{} (sync conflict)", synced.metadata.subject);
+    conflict.metadata.tags.get_or_insert_with(Vec::new).push(CONFLICT_TAG.to_string());
+    conflict
+}
+
+// Store the attachments of synced from the remote directory dir, and return
+// them.
+fn store_attachments(dir: &path::Path, synced: &SyncedTip) -> Vec<crate::attachment::Attachment> {
+    synced.attachments.iter()
+        .map(|attachment| {
+            let file = dir.join("attachments").join(&attachment.sha256);
+            match fs::read(&file) {
+                Ok(contents) => crate::attachment::Attachment::store(&attachment.name, &contents),
+                Err(error) => {
+                    panic!("Error reading attachment {}\n{}", file.display(), error)
+                },
+            }
+        })
+        .collect()
+}
+
+// Load the tips of the remote directory dir, empty if never synced
+fn load_remote(dir: &path::Path) -> Remote {
+    let file = dir.join(TIPS_FILE);
+    if !file.exists() {
+        return Remote::default();
+    }
+
+    let contents = crate::helpers::read_to_string(&file.to_string_lossy());
+    match serde_yaml::from_str(&contents) {
+        Ok(remote) => remote,
+        Err(error) => {
+            panic!("Error deserialize remote tips {}\n{}", file.display(), error)
+        },
+    }
+}
+
+// Write merged to the remote directory dir, copying the attachment contents
+// of the local tips and removing those no longer used.
+fn store_remote(dir: &path::Path, merged: &Remote, tips: &crate::tips::Tips) {
+    let serialized = match serde_yaml::to_string(merged) {
+        Ok(serialized) => serialized,
+        Err(error) => {
+            panic!("Error serialize remote tips\n{}", error)
+        },
+    };
+    crate::helpers::write_to_file(&dir.join(TIPS_FILE).to_string_lossy(), &serialized);
+
+    let attachments = dir.join("attachments");
+    crate::helpers::create_dir_all(&attachments.to_string_lossy());
+    for attachment in tips.tips.iter().flat_map(|tip| tip.attachments.iter()) {
+        let file = attachments.join(&attachment.sha256);
+        if !file.exists() {
+            if let Err(error) = fs::copy(attachment.path(), &file) {
+                panic!("Error copying attachment to {}\n{}", file.display(), error)
+            }
+        }
+    }
+
+    let used: BTreeSet<String> = merged.tips.iter()
+        .flat_map(|synced| synced.attachments.iter().map(|a| a.sha256.to_string()))
+        .collect();
+    let entries = match fs::read_dir(&attachments) {
+        Ok(entries) => entries,
+        Err(error) => {
+            panic!("Error reading directory {}\n{}", attachments.display(), error)
+        },
+    };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        if !used.contains(&*entry.file_name().to_string_lossy()) {
+            crate::helpers::remove_file(&entry.path().to_string_lossy());
+        }
+    }
+}
+
+// Load the base of the last sync, empty if never synced
+fn load_base() -> Base {
+    let file = CONFIG.home().join("sync_base.yaml");
+    if !file.exists() {
+        return Base::default();
+    }
+
+    let contents = crate::helpers::read_to_string(&file.to_string_lossy());
+    match serde_yaml::from_str(&contents) {
+        Ok(base) => base,
+        Err(error) => {
+            panic!("Error deserialize sync base {}\n{}", file.display(), error)
+        },
+    }
+}
+
+// Write the base of the last sync
+fn store_base(base: &Base) {
+    let serialized = match serde_yaml::to_string(base) {
+        Ok(serialized) => serialized,
+        Err(error) => {
+            panic!("Error serialize sync base\n{}", error)
+        },
+    };
+
+    let file = CONFIG.home().join("sync_base.yaml");
+    crate::helpers::write_to_file(&file.to_string_lossy(), &serialized);
+}
+
+// Return true if remote is a git repository: a URL, a path ending in .git or
+// a bare repository. Any other path is a plain directory.
+fn is_git(remote: &str) -> bool {
+    let path = path::Path::new(remote);
+    remote.contains("://") ||
+        remote.starts_with("git@") ||
+        remote.ends_with(".git") ||
+        (path.join("HEAD").is_file() && path.join("objects").is_dir())
+}
+
+// Remove the clone of a git remote
+fn remove_clone(clone: &path::Path) {
+    if let Err(error) = fs::remove_dir_all(clone) {
+        panic!("Error removing directory {}\n{}", clone.display(), error)
+    }
+}
@@ -0,0 +1,17 @@
+// This file contains functionality to list the available syntaxes
+
+
+// Entry point for syntaxes subcommand
+pub fn syntaxes(_matches: &clap::ArgMatches) {
+    let ps = crate::assets::syntax_set();
+
+    // List all syntaxes with the data extensions selecting them
+    let mut syntaxes: Vec<_> = ps.syntaxes().iter()
+        .filter(|syntax| !syntax.hidden)
+        .collect();
+    syntaxes.sort_by_key(|syntax| syntax.name.to_lowercase());
+
+    for syntax in syntaxes {
+        println!("{}: {}", syntax.name, syntax.file_extensions.join(", "));
+    }
+}
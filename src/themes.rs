@@ -0,0 +1,15 @@
+// This file contains functionality to list the available themes
+
+use crate::statics::{CONFIG};
+
+
+// Entry point for themes subcommand
+pub fn themes(_matches: &clap::ArgMatches) {
+    let ts = crate::assets::theme_set();
+
+    // List all themes, marking the configured theme
+    for name in ts.themes.keys() {
+        let marker = if name == &CONFIG.style.data.theme { "*" } else { " " };
+        println!("{} {}", marker, name);
+    }
+}
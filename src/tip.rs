@@ -20,8 +20,18 @@ pub struct Tip {
     pub metadata: crate::metadata::Metadata,
 
     // The tips data is the actual tip it self. It is stored in a
-    // separate file with a name same as this field.
+    // separate file with a name same as this field. The uuid never changes,
+    // so it is also the stable identity of the Tip, unlike the id.
     pub data: uuid::Uuid,
+
+    // Additional named parts of the tip data, each stored in a separate
+    // file. See Part type for more info.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<crate::part::Part>,
+
+    // Files attached to the tip. See Attachment type for more info.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<crate::attachment::Attachment>,
 }
 
 impl fmt::Display for Tip {
@@ -47,72 +57,71 @@ impl Tip {
         crate::present::present(&rows);
     }
 
-    // List of Cells for all metadata of Tip
+    // List of Cells for the configured list columns of Tip
     pub fn header_cells(&self) -> Vec<prettytable::Cell> {
-        vec![self.id_cell(), self.subject_cell(), self.tags_cell()]
+        let columns = crate::columns::configured();
+        crate::columns::rows(&[self], &columns).pop().unwrap_or_default()
     }
 
-    // Cell for metadata.id
-    fn id_cell(&self) -> prettytable::Cell {
-        let mut cell = prettytable::Cell::new(
-            &format!("{}", self.metadata.id.unwrap()))
-            .style_spec(&CONFIG.style.table.id);
-
-        cell.align(prettytable::format::Alignment::LEFT);
-        cell.set_hspan(4);
+    // Return the size in bytes of the data file and the part files
+    pub fn data_size(&self) -> u64 {
+        let data_file = format!("{}/{}", &CONFIG.data, self.data);
+        let files = std::iter::once(data_file).chain(self.parts.iter().map(|part| part.path()));
 
-        cell
+        files.map(|file| match fs::metadata(&file) {
+            Ok(metadata) => metadata.len(),
+            Err(error) => {
+                panic!("Error reading metadata of file {}\n{}",
+                       &file, error)
+            },
+        }).sum()
     }
 
-    // Cell for metadata.subject
-    fn subject_cell(&self) -> prettytable::Cell {
-        let mut cell = prettytable::Cell::new(
-            &self.metadata.subject.to_string())
-            .style_spec(&CONFIG.style.table.subject);
-
-        cell.align(prettytable::format::Alignment::LEFT);
-        cell.set_hspan(4);
-
-        cell
+    // Return the contents of the data file
+    pub fn get_data(&self) -> String {
+        let data_file = format!("{}/{}", &CONFIG.data, self.data);
+        crate::helpers::read_to_string(&data_file)
     }
 
-    // Cell for metadata.tags
-    fn tags_cell(&self) -> prettytable::Cell {
-
-        // Create a new String containing all tags separated
-        // by a space
-        let mut tag_string = String::new();
-
-        if let Some(tags) = &self.metadata.tags {
-            for tag in tags.iter() {
-                tag_string.insert(0, ' ');
-                tag_string.insert_str(0, &tag);
-            }
-        };
-
-        // Create the cell with the string created
-        // and set the style, alignment
-        let mut cell = prettytable::Cell::new(&tag_string)
-            .style_spec(&CONFIG.style.table.tags);
-
-        cell.align(prettytable::format::Alignment::RIGHT);
-        cell.set_hspan(4);
+    // Return the contents of the data file followed by the contents of the
+    // parts, used when searching the Tip
+    pub fn get_all_data(&self) -> String {
+        let mut data = self.get_data();
+        for part in &self.parts {
+            data.push('\n');
+            data.push_str(&part.get_data());
+        }
+        data
+    }
 
-        cell
+    // Return the first characters of the uuid, enough to refer to the Tip
+    pub fn short_uuid(&self) -> String {
+        self.data.to_simple().to_string()[..8].to_string()
     }
 
-    // Return the contents of the data file
-    pub fn get_data(&self) -> String {
-        let data_file = format!("{}/{}", &CONFIG.data, self.data);
-        crate::helpers::read_to_string(&data_file)
+    // Return the part with name
+    pub fn get_part(&self, name: &str) -> Option<&crate::part::Part> {
+        self.parts.iter().find(|part| part.name == name)
     }
 
     // Write the Tip structure to file, adding the contents of data
     // separated by the separator string.
     pub fn to_file(&self, outfile: &str) {
 
-        // Serialize Tip to string
-        let serialized_tip = match serde_yaml::to_string(&*self) {
+        // Serialize Tip to string. The parts are edited below the data, and
+        // attachments are not edited, so they are left out.
+        let mut value = match serde_yaml::to_value(&*self) {
+            Ok(value) => value,
+            Err(error) => {
+                panic!("Error serialize tip {}\n{}",
+                       &*self, error)
+            },
+        };
+        if let Some(mapping) = value.as_mapping_mut() {
+            mapping.remove(&serde_yaml::Value::from("parts"));
+            mapping.remove(&serde_yaml::Value::from("attachments"));
+        }
+        let serialized_tip = match serde_yaml::to_string(&value) {
             Ok(data) => data,
             Err(error) => {
                 panic!("Error serialize tip {}\n{}",
@@ -134,25 +143,63 @@ impl Tip {
         };
 
         // Insert all into a String and finally write to file
-        let mut data = String::new();
-        data.insert_str(0, tip_contents.as_str());
+        let mut data = crate::part::join(&tip_contents, &self.parts);
         data.insert_str(0, separator.as_str());
         data.insert_str(0, serialized_tip.as_str());
         crate::helpers::write_to_file(&outfile, &data);
     }
 
-    // Present (print to stdout) this tip
-    pub fn present(&self) {
+    // Present (print to stdout) this tip, the data followed by the parts
+    pub fn present(&self, options: &crate::present::Options) {
 
         // Read the data contents
-        let data_file = format!("{}/{}", &CONFIG.data, &self.data);
-        let data = crate::helpers::read_to_string(&data_file);
-
-        // Present the
-        crate::present::present_tip(
-            self.header_cells(),
-            &data,
-            self.metadata.data_extension.clone());
+        let mut sections = vec![crate::present::Section {
+            title: None,
+            data: self.get_data(),
+            extension: self.metadata.data_extension.clone(),
+        }];
+
+        for part in &self.parts {
+            sections.push(crate::present::Section {
+                title: Some(crate::part::separator(&part.name, &part.extension)),
+                data: part.get_data(),
+                extension: part.extension.clone(),
+            });
+        }
+
+        // List the attachments, not their contents
+        if !self.attachments.is_empty() {
+            let lines: Vec<String> = self.attachments.iter()
+                .map(|a| format!("{}  {}  {}\n", a.name, crate::columns::human_size(a.size), a.mime))
+                .collect();
+            sections.push(crate::present::Section {
+                title: Some("----- TIP ATTACHMENTS -----".to_string()),
+                data: lines.concat(),
+                extension: Some("txt".to_string()),
+            });
+        }
+
+        crate::present::present_tip(self.header_cells(), &sections, options);
+    }
+
+    // Present (print to stdout) the part with name of this tip. Will panic if
+    // there is no such part.
+    pub fn present_part(&self, name: &str, options: &crate::present::Options) {
+        let part = match self.get_part(name) {
+            Some(part) => part,
+            None => {
+                panic!("No part {} in tip with ID: {}",
+                       name, self.metadata.id.unwrap_or(0))
+            },
+        };
+
+        let sections = [crate::present::Section {
+            title: None,
+            data: part.get_data(),
+            extension: part.extension.clone(),
+        }];
+
+        crate::present::present_tip(self.header_cells(), &sections, options);
     }
 }
 
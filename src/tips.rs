@@ -2,14 +2,28 @@
 
 use crate::statics::{CONFIG};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap};
 use std::fmt;
 
 
+// Shortest uuid prefix accepted as a reference to a Tip
+const MIN_PREFIX: usize = 4;
+
 // Tips struct only contains a list of Tip. All tip(s) information goes into
 // a Tip. However operation on all tips are handled as methods of tips.
 #[derive(Serialize, Deserialize)]
 pub struct Tips {
 
+    // The highest id ever given to a Tip, kept up to date when storing, so
+    // ids of removed tips are not given to new tips.
+    #[serde(default)]
+    pub last_id: usize,
+
+    // Ids changed by renumber, old id to new id, so old ids still refer to
+    // the same Tip. An alias is dropped once a Tip is given its old id.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<usize, usize>,
+
     // List of tip structs that holds all tip information
     pub tips: Vec<crate::tip::Tip>,
 }
@@ -29,7 +43,11 @@ impl Tips {
     // Functions
 
     // This function replaces the DB file on disk with contents of it self
-    pub fn store(&self) {
+    pub fn store(&mut self) {
+        self.last_id = self.highest_id();
+        let ids: Vec<usize> = self.tips.iter().filter_map(|tip| tip.metadata.id).collect();
+        self.aliases.retain(|old, _| !ids.contains(old));
+
         let serialized = match serde_yaml::to_string(&*self) {
             Ok(string) => string,
             Err(error) => {
@@ -41,7 +59,9 @@ impl Tips {
         crate::helpers::write_to_file(&CONFIG.db_file, &serialized);
     }
 
-    // Return the index of tip matching given ID
+    // Return the index of the first tip matching given ID. References given by
+    // the user are resolved with find, which rejects an id used by several
+    // tips.
     pub fn get_tip_index(&self, id: usize) -> Option<usize> {
         for (index, tip) in self.tips.iter().enumerate() {
             if tip.metadata.id.unwrap() == id {
@@ -51,20 +71,29 @@ impl Tips {
         None
     }
 
-    // Associated functions
-
-    // This function presents a summary of the tips struct to the user
-    pub fn summary() {
-        let tips: Tips = Tips::load();
-        let mut rows = Vec::new();
+    // Return the highest id ever given to a Tip
+    pub fn highest_id(&self) -> usize {
+        self.tips.iter()
+            .filter_map(|tip| tip.metadata.id)
+            .fold(self.last_id, usize::max)
+    }
 
-        for tip in tips.tips.iter() {
-            rows.push(tip.header_cells())
-        };
+    // Return the index of the tip referred to by reference, see find. An id
+    // changed by renumber refers to the Tip by its alias.
+    pub fn find(&self, reference: &str) -> usize {
+        if let Ok(id) = reference.parse::<usize>() {
+            if self.get_tip_index(id).is_none() {
+                if let Some(index) = self.aliases.get(&id).and_then(|new| self.get_tip_index(*new)) {
+                    return index;
+                }
+            }
+        }
 
-        crate::present::present(&rows);
+        find(self.tips.iter(), reference)
     }
 
+    // Associated functions
+
     // Load the current DB into a Tips struct, and then returns it to caller
     pub fn load() -> Tips {
         let contents = crate::helpers::read_to_string(&CONFIG.db_file);
@@ -79,19 +108,12 @@ impl Tips {
         tips
     }
 
-    // Function to get the next available Tip ID
+    // Function to get the next available Tip ID, above the highest id ever
+    // given so the id of a removed Tip is not reused
     pub fn next_id() -> Option<usize> {
         let tips: Tips = Tips::load();
 
-        let num_tips = tips.tips.len();
-        let last_tip = match tips.tips.get(num_tips - 1) {
-            Some(tip) => tip,
-            None => {
-                panic!("Error unable to get next id from tips vector")
-            },
-        };
-
-        Some(last_tip.metadata.id.unwrap() + 1)
+        Some(tips.highest_id() + 1)
     }
 
     // Add a Tip to the database
@@ -101,3 +123,47 @@ impl Tips {
         tips.store();
     }
 }
+
+// Return the position in tips of the Tip referred to by reference, either its
+// id or a unique prefix of its uuid (with or without dashes), like git short
+// hashes. Exits with a message if no Tip, or more than one, matches, as when
+// several tips have the id.
+pub fn find<'a, I>(tips: I, reference: &str) -> usize
+where I: Iterator<Item = &'a crate::tip::Tip> + Clone {
+    if let Ok(id) = reference.parse::<usize>() {
+        let matching: Vec<(usize, &crate::tip::Tip)> = tips.clone().enumerate()
+            .filter(|(_, tip)| tip.metadata.id == Some(id))
+            .collect();
+
+        match matching.len() {
+            0 => (),
+            1 => return matching[0].0,
+            _ => ambiguous(reference, &matching),
+        }
+    }
+
+    let prefix = reference.to_lowercase().replace('-', "");
+    if prefix.len() >= MIN_PREFIX && prefix.chars().all(|c| c.is_ascii_hexdigit()) {
+        let matching: Vec<(usize, &crate::tip::Tip)> = tips.enumerate()
+            .filter(|(_, tip)| tip.data.to_simple().to_string().starts_with(&prefix))
+            .collect();
+
+        match matching.len() {
+            0 => (),
+            1 => return matching[0].0,
+            _ => ambiguous(reference, &matching),
+        }
+    }
+
+    eprintln!("No tip with id or uuid: {}", reference);
+    std::process::exit(1);
+}
+
+// Exit listing the tips matching an ambiguous reference by uuid
+fn ambiguous(reference: &str, matching: &[(usize, &crate::tip::Tip)]) -> ! {
+    eprintln!("Ambiguous tip reference: {}, it matches", reference);
+    for (_, tip) in matching {
+        eprintln!("  {}  {:>4}  {}", tip.data, tip.metadata.id.unwrap_or(0), tip.metadata.subject);
+    }
+    std::process::exit(1);
+}
@@ -0,0 +1,177 @@
+// This file contains functionality to keep removed tips in a trash (trash
+// subcommand), so a removal can be undone.
+//
+// Removed tips are moved from the database to trash.yaml in the tips home,
+// together with the time they were removed. Their data, part, attachment
+// and revision files are kept until the trash is emptied.
+
+use crate::statics::{CONFIG};
+use chrono::DateTime;
+use chrono::offset::Local;
+use serde::{Deserialize, Serialize};
+use std::{path};
+
+
+// Struct holding the removed tips
+#[derive(Serialize, Deserialize, Default)]
+pub struct Trash {
+    pub tips: Vec<TrashedTip>,
+}
+
+// Struct holding a removed Tip and when it was removed
+#[derive(Serialize, Deserialize)]
+pub struct TrashedTip {
+    pub removed: DateTime<Local>,
+    pub tip: crate::tip::Tip,
+}
+
+impl Trash {
+
+    // Functions
+
+    // Replace the trash file on disk with contents of it self
+    pub fn store(&self) {
+        let serialized = match serde_yaml::to_string(self) {
+            Ok(string) => string,
+            Err(error) => {
+                panic!("Error serialize trash\n{}", error)
+            },
+        };
+
+        crate::helpers::write_to_file(&file().to_string_lossy(), &serialized);
+    }
+
+    // Associated functions
+
+    // Load the trash, empty if nothing was removed yet
+    pub fn load() -> Trash {
+        let file = file();
+        if !file.exists() {
+            return Trash::default();
+        }
+
+        let contents = crate::helpers::read_to_string(&file.to_string_lossy());
+        match serde_yaml::from_str(&contents) {
+            Ok(trash) => trash,
+            Err(error) => {
+                panic!("Error deserialize trash {}\n{}",
+                       file.display(), error)
+            },
+        }
+    }
+}
+
+// Entry point for trash subcommand
+pub fn trash(matches: &clap::ArgMatches) {
+    match matches.subcommand() {
+        ("list", Some(matches))    => list(matches),
+        ("restore", Some(matches)) => restore(matches),
+        ("empty", Some(matches))   => empty(matches),
+        _                          => {
+            println!("{}", matches.usage());
+            std::process::exit(1);
+        },
+    }
+}
+
+// Move tip to the trash
+pub fn put(tip: crate::tip::Tip) {
+    let mut trash = Trash::load();
+    trash.tips.push(TrashedTip { removed: Local::now(), tip });
+    trash.store();
+}
+
+// List the removed tips, most recently removed first
+fn list(_matches: &clap::ArgMatches) {
+    let trash = Trash::load();
+    if trash.tips.is_empty() {
+        println!("Trash is empty");
+        return;
+    }
+
+    for trashed in trash.tips.iter().rev() {
+        println!("{:>4}  {}  {}",
+                 trashed.tip.metadata.id.unwrap_or(0),
+                 trashed.removed.format("%Y-%m-%d %H:%M:%S"),
+                 trashed.tip.metadata.subject);
+    }
+}
+
+// Restore a removed Tip, with a new id if its id was taken since
+fn restore(matches: &clap::ArgMatches) {
+
+    // Since arg "id" is required it is safe to call unwrap here. The same id
+    // may have been removed more than once, then the uuid tells them apart.
+    let mut trash = Trash::load();
+    let position = crate::tips::find(trash.tips.iter().map(|t| &t.tip), matches.value_of("id").unwrap());
+
+    let mut tips = crate::tips::Tips::load();
+    let mut tip = trash.tips.remove(position).tip;
+    let id = tip.metadata.id.unwrap_or(0);
+    if tips.get_tip_index(id).is_some() {
+        tip.metadata.id = crate::tips::Tips::next_id();
+        println!("Id {} is taken, restored as tip {}", id, tip.metadata.id.unwrap());
+    }
+
+    // Keep the tips ordered by id
+    let new_id = tip.metadata.id;
+    let message = crate::git::message("recover", &tip);
+    let index = tips.tips.iter().position(|t| t.metadata.id > new_id).unwrap_or(tips.tips.len());
+    tips.tips.insert(index, tip);
+    tips.store();
+    trash.store();
+    crate::git::commit(&message);
+}
+
+// Permanently remove the tips in the trash, or only those removed before the
+// --older-than duration.
+fn empty(matches: &clap::ArgMatches) {
+    let limit = matches.value_of("older-than").map(|older_than| {
+        match parse_duration(older_than) {
+            Some(duration) => Local::now() - duration,
+            None => {
+                eprintln!("Invalid duration: {}, expected for example 30d, 12h or 2w", older_than);
+                std::process::exit(1);
+            },
+        }
+    });
+
+    let trash = Trash::load();
+    let (purged, kept): (Vec<TrashedTip>, Vec<TrashedTip>) = trash.tips.into_iter()
+        .partition(|trashed| match limit {
+            Some(limit) => trashed.removed < limit,
+            None        => true,
+        });
+
+    // Store first, so the files of the purged tips are no longer referenced
+    let trash = Trash { tips: kept };
+    trash.store();
+
+    let tips = crate::tips::Tips::load();
+    for trashed in &purged {
+        crate::remove::purge(&trashed.tip, &tips);
+    }
+
+    crate::git::commit(&format!("empty trash, {} tip(s) removed", purged.len()));
+    println!("Removed {} tip(s) from trash", purged.len());
+}
+
+// Parse a duration such as 30d, 12h, 2w or 45m
+fn parse_duration(text: &str) -> Option<chrono::Duration> {
+    let text = text.trim();
+    let unit = text.chars().last()?;
+    let number: i64 = text[..text.len() - unit.len_utf8()].parse().ok()?;
+
+    match unit {
+        'm' => Some(chrono::Duration::minutes(number)),
+        'h' => Some(chrono::Duration::hours(number)),
+        'd' => Some(chrono::Duration::days(number)),
+        'w' => Some(chrono::Duration::weeks(number)),
+        _   => None,
+    }
+}
+
+// Path of the trash file
+fn file() -> path::PathBuf {
+    CONFIG.home().join("trash.yaml")
+}
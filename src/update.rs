@@ -2,23 +2,34 @@
 
 use crate::statics::CONFIG;
 use crate::tip::TIP;
-use std::str::{FromStr};
 
 
 // Entry point for update subcommand
 pub fn update(matches: &clap::ArgMatches) {
 
-    // arg id is mandatory, so unwrap is safe. Then convert id to usize
-    let id = match usize::from_str(matches.value_of("id").unwrap()) {
-        Ok(id) => id,
-        Err(error) => {
-            panic!("Error convert str to usize\n{}", error)
-        },
-    };
+    // arg id is mandatory, so unwrap is safe. It is an id or uuid prefix.
+    let tips = crate::tips::Tips::load();
+    let index = tips.find(matches.value_of("id").unwrap());
+
+    update_tip(index);
+}
+
+// Update the Tip at index in the database by letting the user edit it in the
+// configured editor.
+pub fn update_tip(index: usize) {
 
     // Possible metadata updates require mutable tips and tip initializing
     let mut tips = crate::tips::Tips::load();
-    let mut tip = get_tip_with_id(id, &mut tips);
+    let tip = match tips.tips.get_mut(index) {
+        Some(tip) => tip,
+        None => {
+            panic!("Error unable to get mut reference of tip with index {}",
+                   index)
+        },
+    };
+
+    // Keep the current version if it was never recorded, see revision.rs
+    crate::revision::ensure(tip);
 
     // Write tip to file then open it with configured editor
     tip.to_file(&CONFIG.tmp_file);
@@ -28,52 +39,83 @@ pub fn update(matches: &clap::ArgMatches) {
     let contents = crate::helpers::read_to_string(&CONFIG.tmp_file);
     crate::helpers::remove_file(&CONFIG.tmp_file);
 
-    // Separate metadata and data
+    // Separate metadata and data, and the data from its parts
     let container = extrace_metadata_data(&contents);
-
-    // Update data and metadata & then store tips if something was updated.
-    if  update_data(&tip, &container.data) ||
-        update_metadata(&mut tip, &container.metadata) {
-            tip.metadata.last_updated = Some(chrono::offset::Local::now());
-            tips.store();
-    }
-}
-
-// Get Tip given ID
-fn get_tip_with_id(id: usize,
-               tips: &mut crate::tips::Tips) -> &mut crate::tip::Tip {
-
-    // Locate index of Tip given ID or panic since no Tip with ID was found
-    if let Some(index) = tips.get_tip_index(id) {
-
-        // Return mut Tip reference or panic.
-        return match tips.tips.get_mut(index) {
-            Some(tip) => tip,
-            None => {
-                panic!("Error unable to get mut reference of tip with index {}",
-                       index)
-            },
-        };
-    } else {
-        panic!("Error id {} not found in DB", id)
+    let (data, mut sections) = crate::part::split(&container.data);
+    let data = crate::part::unjoin(&tip.get_data(), &tip.parts, data, &mut sections);
+
+    // Update data, parts and metadata & then store tips if something was
+    // updated.
+    let data_updated = update_data(tip, &data);
+    let parts_updated = update_parts(tip, sections);
+    let metadata_updated = update_metadata(tip, &container.metadata);
+    if data_updated || parts_updated || metadata_updated {
+        tip.metadata.last_updated = Some(chrono::offset::Local::now());
+        crate::revision::record(tip, "updated");
+        let message = crate::git::message("update", tip);
+        tips.store();
+        crate::git::commit(&message);
     }
 }
 
 // Write Tip data to file if it differ
-fn update_data(tip: &crate::tip::Tip, data: &str) -> bool {
+pub fn update_data(tip: &crate::tip::Tip, data: &str) -> bool {
 
     // Compare data in updated Tip with data stored and only if it differ
     // write the new data to file, replacing old data.
     if data != tip.get_data() {
         crate::helpers::write_to_file(
             &format!("{}/{}", &CONFIG.data, &tip.data),
-            &data);
+            data);
         true
     } else {
         false
     }
 }
 
+// Update the Tip's parts given the parts in the updated file. Parts are
+// matched by name: new parts are created, removed parts have their data file
+// removed and remaining parts are updated if they differ.
+pub fn update_parts(tip: &mut crate::tip::Tip, sections: Vec<crate::part::Section>) -> bool {
+    let mut updated = false;
+    let mut old_parts = std::mem::take(&mut tip.parts);
+
+    for section in sections {
+        match old_parts.iter().position(|part| part.name == section.name) {
+            Some(position) => {
+
+                // Parts before this one were reordered or removed
+                if position != 0 {
+                    updated = true;
+                }
+
+                let mut part = old_parts.remove(position);
+                if part.extension != section.extension {
+                    part.extension = section.extension;
+                    updated = true;
+                }
+                if part.get_data() != section.data {
+                    crate::helpers::write_to_file(&part.path(), &section.data);
+                    updated = true;
+                }
+                tip.parts.push(part);
+            },
+            None => {
+                tip.parts.append(&mut crate::part::create(vec![section]));
+                updated = true;
+            },
+        }
+    }
+
+    // Parts left were removed from the file
+    for part in old_parts {
+        crate::helpers::remove_file(&part.path());
+        updated = true;
+    }
+
+    updated
+}
+
 // Update the Tip's metadata if it differ. This is done by creating a temporary
 // Tip using the metadata received and comparing it against original Tip
 fn update_metadata(tip: &mut crate::tip::Tip, metadata: &str) -> bool {